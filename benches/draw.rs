@@ -0,0 +1,216 @@
+//! Measures drawing of the graph with heavy node payloads.
+//!
+//! Reports the frame time of the widget and compares the drawing loop of the widget, which
+//! borrows the endpoint nodes of the edges, with the previous loop, which cloned them together
+//! with the props of every element. Both loops draw the same graph in the same frames, so their
+//! ratio depends less on the machine load than the absolute times. Times are medians of
+//! [`SAMPLES`] samples.
+//!
+//! Run with `cargo bench --bench draw`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use egui::{CentralPanel, Context, LayerId, Pos2, RawInput, Rect, Shape, Vec2};
+use egui_graphs::{
+    DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, DrawContext, EdgeProps, Graph,
+    GraphView, LayoutRandom, LayoutStateRandom, Metadata, NodeProps, SettingsStyle,
+};
+use petgraph::stable_graph::{DefaultIx, NodeIndex, StableGraph};
+use petgraph::Directed;
+
+const NODES: usize = 1_000;
+const EDGES_PER_NODE: usize = 3;
+const PAYLOAD_SIZE: usize = 4 * 1024;
+const SAMPLES: usize = 100;
+const WARMUP: usize = 10;
+
+type BenchGraph = Graph<Vec<u8>, ()>;
+
+fn main() {
+    bench_frame("frame", &SettingsStyle::new());
+    bench_frame(
+        "frame with labels",
+        &SettingsStyle::new().with_labels_always(true),
+    );
+    bench_draw_loops(&SettingsStyle::new());
+}
+
+fn graph() -> BenchGraph {
+    let mut g = StableGraph::new();
+    for i in 0..NODES {
+        g.add_node(vec![i as u8; PAYLOAD_SIZE]);
+    }
+    for i in 0..NODES {
+        for j in 1..=EDGES_PER_NODE {
+            g.add_edge(NodeIndex::new(i), NodeIndex::new((i + j) % NODES), ());
+        }
+    }
+    Graph::from(&g)
+}
+
+fn input() -> RawInput {
+    RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::new(1920., 1080.))),
+        ..Default::default()
+    }
+}
+
+fn median(mut samples: Vec<Duration>) -> Duration {
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+/// Frame time of the widget.
+fn bench_frame(name: &str, settings_style: &SettingsStyle) {
+    let mut g = graph();
+    let ctx = Context::default();
+    let mut frame = || {
+        let start = Instant::now();
+        let output = ctx.run(input(), |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.add(
+                    &mut GraphView::<
+                        _,
+                        _,
+                        Directed,
+                        DefaultIx,
+                        DefaultNodeShape,
+                        DefaultEdgeShape,
+                        LayoutStateRandom,
+                        LayoutRandom,
+                    >::new(&mut g)
+                    .with_styles(settings_style),
+                );
+            });
+        });
+        black_box(output);
+        start.elapsed()
+    };
+
+    // the first frames fit the graph to the screen and warm up the caches
+    for _ in 0..WARMUP {
+        frame();
+    }
+    let samples = (0..SAMPLES).map(|_| frame()).collect::<Vec<_>>();
+
+    println!(
+        "{name}: {NODES} nodes, {} edges, {PAYLOAD_SIZE} bytes payload: {:?} per frame",
+        NODES * EDGES_PER_NODE,
+        median(samples),
+    );
+}
+
+/// Draws the graph with the borrowing and the cloning loop in the same frames.
+fn bench_draw_loops(settings_style: &SettingsStyle) {
+    let mut g = graph();
+    let ctx = Context::default();
+    let meta = Metadata::default();
+    let mut frame = || {
+        let mut times = (Duration::ZERO, Duration::ZERO);
+        let output = ctx.run(input(), |ctx| {
+            // nothing is culled, so both loops draw every element
+            let painter = ctx
+                .layer_painter(LayerId::background())
+                .with_clip_rect(Rect::EVERYTHING);
+            let draw_ctx = DrawContext {
+                ctx,
+                painter: &painter,
+                style: settings_style,
+                is_directed: true,
+                meta: &meta,
+            };
+
+            let start = Instant::now();
+            draw_borrowing(&mut g, &draw_ctx);
+            times.0 = start.elapsed();
+
+            let start = Instant::now();
+            draw_cloning(&mut g, &draw_ctx);
+            times.1 = start.elapsed();
+        });
+        black_box(output);
+        times
+    };
+
+    for _ in 0..WARMUP {
+        frame();
+    }
+    let (borrowing, cloning): (Vec<_>, Vec<_>) = (0..SAMPLES).map(|_| frame()).unzip();
+    let (borrowing, cloning) = (median(borrowing), median(cloning));
+
+    println!(
+        "draw: {:?} borrowing endpoint nodes, {:?} cloning them, {:.2}x faster",
+        borrowing,
+        cloning,
+        cloning.as_secs_f64() / borrowing.as_secs_f64(),
+    );
+}
+
+/// Drawing loop of the widget: displays are swapped out of the graph, so they are updated
+/// from the props and the edges are drawn with the endpoint nodes borrowed from the graph.
+fn draw_borrowing(g: &mut BenchGraph, ctx: &DrawContext) {
+    let mut shapes = Vec::new();
+    let indices = g.g.edge_indices().collect::<Vec<_>>();
+    let Some(mut display) = indices.first().map(|idx| g.g[*idx].display().clone()) else {
+        return;
+    };
+    for idx in indices {
+        std::mem::swap(&mut display, g.g[idx].display_mut());
+        update_edge(&mut display, g.g[idx].props());
+
+        let (idx_start, idx_end) = g.edge_endpoints(idx).unwrap();
+        let (start, end) = (&g.g[idx_start], &g.g[idx_end]);
+        shapes.extend(display.shapes(start, end, ctx));
+        std::mem::swap(&mut display, g.g[idx].display_mut());
+    }
+    let indices = g.g.node_indices().collect::<Vec<_>>();
+    let Some(mut display) = indices.first().map(|idx| g.g[*idx].display().clone()) else {
+        return;
+    };
+    for idx in indices {
+        let n = &mut g.g[idx];
+        std::mem::swap(&mut display, n.display_mut());
+        shapes.extend(node_shapes(&mut display, n.props(), ctx));
+        std::mem::swap(&mut display, n.display_mut());
+    }
+    ctx.painter.extend(shapes);
+}
+
+/// Drawing loop before the edge displays were swapped out of the graph: the props of every
+/// element and the endpoint nodes of every edge are cloned.
+fn draw_cloning(g: &mut BenchGraph, ctx: &DrawContext) {
+    let mut shapes = Vec::new();
+    for idx in g.g.edge_indices().collect::<Vec<_>>() {
+        let (idx_start, idx_end) = g.edge_endpoints(idx).unwrap();
+        let start = g.node(idx_start).cloned().unwrap();
+        let end = g.node(idx_end).cloned().unwrap();
+
+        let e = &mut g.g[idx];
+        let props = e.props().clone();
+        let display = e.display_mut();
+        update_edge(display, &props);
+        shapes.extend(display.shapes(&start, &end, ctx));
+    }
+    for idx in g.g.node_indices().collect::<Vec<_>>() {
+        let n = &mut g.g[idx];
+        let props = n.props().clone();
+        shapes.extend(node_shapes(n.display_mut(), &props, ctx));
+    }
+    ctx.painter.extend(shapes);
+}
+
+// Default shapes implement the display traits for any graph types, so the types are given
+// explicitly.
+fn node_shapes(
+    display: &mut DefaultNodeShape,
+    props: &NodeProps<Vec<u8>>,
+    ctx: &DrawContext,
+) -> Vec<Shape> {
+    DisplayNode::<Vec<u8>, (), Directed, DefaultIx>::update(display, props);
+    DisplayNode::<Vec<u8>, (), Directed, DefaultIx>::shapes(display, ctx)
+}
+
+fn update_edge(display: &mut DefaultEdgeShape, props: &EdgeProps<()>) {
+    DisplayEdge::<Vec<u8>, (), Directed, DefaultIx, DefaultNodeShape>::update(display, props);
+}
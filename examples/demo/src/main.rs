@@ -523,6 +523,11 @@ impl App for DemoApp {
         self.sync();
         self.update_simulation();
         self.update_fps();
+
+        // the simulation moves the nodes outside of the widget, so it does not repaint itself
+        if !self.simulation_stopped {
+            ctx.request_repaint();
+        }
     }
 }
 
@@ -1,16 +1,18 @@
 use eframe::{run_native, App, CreationContext};
 use egui::Context;
-use egui_graphs::{DefaultGraphView, Graph, SettingsInteraction, SettingsStyle};
+use egui_graphs::{DefaultGraphView, Graph, SettingsInteraction, SettingsStyle, Subgraph};
 use petgraph::stable_graph::StableGraph;
 
 pub struct InteractiveApp {
     g: Graph,
+    clipboard: Option<Subgraph<(), ()>>,
 }
 
 impl InteractiveApp {
     fn new(_: &CreationContext<'_>) -> Self {
-        let g: Graph = generate_graph();
-        Self { g }
+        let mut g: Graph = generate_graph();
+        g.set_history_enabled(true);
+        Self { g, clipboard: None }
     }
 }
 
@@ -24,12 +26,36 @@ impl App for InteractiveApp {
                 .with_node_selection_multi_enabled(true)
                 .with_edge_clicking_enabled(true)
                 .with_edge_selection_enabled(true)
-                .with_edge_selection_multi_enabled(true);
-            let style_settings = &SettingsStyle::new().with_labels_always(true);
+                .with_edge_selection_multi_enabled(true)
+                .with_box_selection_enabled(true)
+                .with_box_selection_edges_enabled(true)
+                .with_edge_creation_enabled(true)
+                .with_editing_enabled(true)
+                .with_undo_redo_keys_enabled(true)
+                .with_label_editing_enabled(true)
+                .with_keyboard_navigation_enabled(true);
+            let style_settings = &SettingsStyle::new()
+                .with_labels_always(true)
+                .with_highlight_neighbors_enabled(true);
             ui.add(
                 &mut DefaultGraphView::new(&mut self.g)
                     .with_styles(style_settings)
-                    .with_interactions(interaction_settings),
+                    .with_interactions(interaction_settings)
+                    .with_node_factory(|_| Some(()))
+                    .with_edge_factory(|_, _| Some(()))
+                    .with_clipboard(&mut self.clipboard)
+                    .with_node_tooltip(|ui, n| {
+                        ui.label(format!("node {}", n.label()));
+                    })
+                    .with_edge_tooltip(|ui, e| {
+                        ui.label(format!("edge {}", e.label()));
+                    })
+                    .with_context_menu(|ui, target| {
+                        ui.label(format!("{target:?}"));
+                        if ui.button("Close").clicked() {
+                            ui.close_menu();
+                        }
+                    }),
             );
         });
     }
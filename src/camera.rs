@@ -0,0 +1,183 @@
+use egui::{emath::easing, Context, Id, Vec2};
+use petgraph::stable_graph::{IndexType, NodeIndex};
+
+use crate::Metadata;
+
+const KEY: &str = "egui_graphs_camera";
+
+/// Camera position requested by the application.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum CameraTarget {
+    Node(usize),
+    Nodes(Vec<usize>),
+    All,
+    Zoom(f32),
+    Pan(Vec2),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CameraRequest {
+    pub target: CameraTarget,
+    /// Duration of the animation in seconds
+    pub duration: f32,
+}
+
+/// Animated transition of the camera. Pan is relative to the top left corner of the widget.
+#[derive(Clone, Debug)]
+pub(crate) struct CameraAnimation {
+    pub from_zoom: f32,
+    pub from_pan: Vec2,
+    pub to_zoom: f32,
+    pub to_pan: Vec2,
+    /// Time of the animation start in seconds
+    pub start: f64,
+    pub duration: f32,
+}
+
+impl CameraAnimation {
+    /// Returns zoom and pan at the given time and whether the animation is finished.
+    pub fn at(&self, time: f64) -> (f32, Vec2, bool) {
+        let t = ((time - self.start) as f32 / self.duration).clamp(0., 1.);
+        let eased = easing::cubic_in_out(t);
+        let zoom = self.from_zoom + (self.to_zoom - self.from_zoom) * eased;
+        let pan = self.from_pan + (self.to_pan - self.from_pan) * eased;
+        (zoom, pan, t >= 1.)
+    }
+}
+
+/// Controls the camera of [`crate::GraphView`] from the application code.
+///
+/// Requests are stored in egui memory under the id of the widget and applied by the widget on its
+/// next frame, so the camera can be moved between frames, e.g. from a side panel. Only the last
+/// request is applied. Camera moves emit the usual pan and zoom events.
+///
+/// The widget is addressed by the id set with [`crate::GraphView::with_id`] or by the id of the
+/// response returned when the widget is added.
+///
+/// ```
+/// # use egui_graphs::Camera;
+/// # use petgraph::stable_graph::NodeIndex;
+/// # let ctx = egui::Context::default();
+/// let id = egui::Id::new("graph");
+/// Camera::new(&ctx, id).with_animation(0.3).focus_node(NodeIndex::<u32>::new(0));
+/// ```
+pub struct Camera<'a> {
+    ctx: &'a Context,
+    id: Id,
+    duration: f32,
+}
+
+impl<'a> Camera<'a> {
+    /// Creates the camera of the widget with the given id.
+    pub fn new(ctx: &'a Context, id: Id) -> Self {
+        Self {
+            ctx,
+            id,
+            duration: 0.,
+        }
+    }
+
+    /// Animates the camera move for the given duration in seconds with easing.
+    ///
+    /// Default is 0, the camera moves instantly.
+    pub fn with_animation(mut self, duration: f32) -> Self {
+        self.duration = duration.max(0.);
+        self
+    }
+
+    /// Current zoom factor.
+    pub fn zoom(&self) -> f32 {
+        Metadata::load_from_ctx(self.ctx).zoom
+    }
+
+    /// Current pan offset relative to the top left corner of the widget.
+    pub fn pan(&self) -> Vec2 {
+        let meta = Metadata::load_from_ctx(self.ctx);
+        meta.pan - meta.top_left.to_vec2()
+    }
+
+    /// Centers the node in the widget keeping the zoom.
+    pub fn focus_node<Ix: IndexType>(&self, idx: NodeIndex<Ix>) {
+        self.request(CameraTarget::Node(idx.index()));
+    }
+
+    /// Zooms and pans to fit the nodes in the widget.
+    pub fn fit_nodes<Ix: IndexType>(&self, nodes: &[NodeIndex<Ix>]) {
+        self.request(CameraTarget::Nodes(
+            nodes.iter().map(IndexType::index).collect(),
+        ));
+    }
+
+    /// Zooms and pans to fit the whole graph in the widget.
+    pub fn fit_all(&self) {
+        self.request(CameraTarget::All);
+    }
+
+    /// Sets the zoom factor keeping the center of the widget in place.
+    pub fn set_zoom(&self, zoom: f32) {
+        self.request(CameraTarget::Zoom(zoom));
+    }
+
+    /// Sets the pan offset relative to the top left corner of the widget.
+    pub fn set_pan(&self, pan: Vec2) {
+        self.request(CameraTarget::Pan(pan));
+    }
+
+    fn request(&self, target: CameraTarget) {
+        let request = CameraRequest {
+            target,
+            duration: self.duration,
+        };
+        self.ctx
+            .data_mut(|data| data.insert_temp(self.id.with(KEY), request));
+        self.ctx.request_repaint();
+    }
+
+    /// Takes the pending request of the widget with the given id.
+    pub(crate) fn take_request(ctx: &Context, id: Id) -> Option<CameraRequest> {
+        ctx.data_mut(|data| {
+            let request = data.get_temp::<CameraRequest>(id.with(KEY));
+            data.remove::<CameraRequest>(id.with(KEY));
+            request
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animation() {
+        let animation = CameraAnimation {
+            from_zoom: 1.,
+            from_pan: Vec2::ZERO,
+            to_zoom: 3.,
+            to_pan: Vec2::new(10., 20.),
+            start: 1.,
+            duration: 2.,
+        };
+
+        assert_eq!(animation.at(0.), (1., Vec2::ZERO, false));
+        assert_eq!(animation.at(2.), (2., Vec2::new(5., 10.), false));
+        assert_eq!(animation.at(3.), (3., Vec2::new(10., 20.), true));
+    }
+
+    #[test]
+    fn test_request() {
+        let ctx = Context::default();
+        let (a, b) = (Id::new("a"), Id::new("b"));
+        Camera::new(&ctx, a).set_zoom(2.);
+        Camera::new(&ctx, a).with_animation(1.).fit_all();
+        Camera::new(&ctx, b).set_zoom(3.);
+
+        let request = Camera::take_request(&ctx, a).unwrap();
+        assert_eq!(request.target, CameraTarget::All);
+        assert_eq!(request.duration, 1.);
+        assert!(Camera::take_request(&ctx, a).is_none());
+        assert_eq!(
+            Camera::take_request(&ctx, b).unwrap().target,
+            CameraTarget::Zoom(3.)
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use egui::{Pos2, Vec2};
+use petgraph::{
+    graph::IndexType,
+    stable_graph::{EdgeIndex, NodeIndex},
+    EdgeType,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{DisplayEdge, DisplayNode, Graph, Node, SessionEdge, SessionNode};
+
+/// Nodes and edges copied from a [`Graph`] to be pasted into the same or another graph.
+///
+/// Node locations are relative to the center of the copied nodes, node and edge ids are
+/// the indices in the source graph and are only used to connect the edges.
+///
+/// With the `clipboard` feature the subgraph can be converted to and from JSON text to pass it
+/// between application instances through the system clipboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subgraph<N, E> {
+    /// Center of the copied nodes in the source graph.
+    pub origin: Pos2,
+    pub nodes: Vec<SessionNode<N>>,
+    pub edges: Vec<SessionEdge<E>>,
+}
+
+impl<N, E> Subgraph<N, E>
+where
+    N: Clone,
+    E: Clone,
+{
+    /// Copies selected nodes and the edges between them.
+    /// Returns None if no nodes are selected.
+    pub fn from_selection<Ty, Ix, Dn, De>(g: &Graph<N, E, Ty, Ix, Dn, De>) -> Option<Self>
+    where
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        let selected = g
+            .nodes_iter()
+            .filter(|(_, n)| n.selected())
+            .collect::<Vec<_>>();
+        if selected.is_empty() {
+            return None;
+        }
+
+        let sum = selected
+            .iter()
+            .fold(Vec2::ZERO, |acc, (_, n)| acc + n.location().to_vec2());
+        let origin = (sum / selected.len() as f32).to_pos2();
+
+        let nodes = selected
+            .iter()
+            .map(|(idx, n)| {
+                let props = n.props();
+                SessionNode {
+                    id: idx.index(),
+                    payload: props.payload.clone(),
+                    label: props.label.clone(),
+                    location: (n.location() - origin).to_pos2(),
+                    location_user: None,
+                    color: props.color,
+                    selected: false,
+                }
+            })
+            .collect();
+
+        let edges = g
+            .edges_iter()
+            .filter_map(|(idx, e)| {
+                let (source, target) = g.edge_endpoints(idx)?;
+                let is_copied = |n: NodeIndex<Ix>| g.node(n).is_some_and(Node::selected);
+                if !is_copied(source) || !is_copied(target) {
+                    return None;
+                }
+
+                Some(SessionEdge {
+                    id: idx.index(),
+                    source: source.index(),
+                    target: target.index(),
+                    payload: e.payload().clone(),
+                    label: e.label(),
+                    selected: false,
+                })
+            })
+            .collect();
+
+        Some(Self {
+            origin,
+            nodes,
+            edges,
+        })
+    }
+
+    /// Adds the nodes and the edges to the graph with new indices, centered at the given location.
+    /// Pasted nodes are pinned at their locations. Returns indices of the added nodes and edges.
+    ///
+    /// The paste is recorded in the history of the graph as a single step.
+    pub fn paste_into<Ty, Ix, Dn, De>(
+        &self,
+        g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+        center: Pos2,
+    ) -> (Vec<NodeIndex<Ix>>, Vec<EdgeIndex<Ix>>)
+    where
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        g.history.begin_group();
+
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        let mut idx_by_id = HashMap::with_capacity(self.nodes.len());
+        for n in &self.nodes {
+            let location = center + n.location.to_vec2();
+            let idx =
+                g.add_node_with_label_and_location(n.payload.clone(), n.label.clone(), location);
+            if let (Some(color), Some(node)) = (n.color, g.node_mut(idx)) {
+                node.set_color(color);
+            }
+            idx_by_id.insert(n.id, idx);
+            nodes.push(idx);
+        }
+
+        let edges = self
+            .edges
+            .iter()
+            .filter_map(|e| {
+                let (source, target) = (idx_by_id.get(&e.source)?, idx_by_id.get(&e.target)?);
+                Some(g.add_edge_with_label(*source, *target, e.payload.clone(), e.label.clone()))
+            })
+            .collect();
+
+        g.history.end_group();
+
+        (nodes, edges)
+    }
+}
+
+#[cfg(feature = "clipboard")]
+impl<N, E> Subgraph<N, E>
+where
+    N: Clone + Serialize + for<'de> Deserialize<'de>,
+    E: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Serializes the subgraph to JSON text for the system clipboard.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a payload fails to serialize.
+    pub fn to_clipboard_text(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Parses the subgraph from JSON text produced by [`Subgraph::to_clipboard_text`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the text is not a serialized subgraph.
+    pub fn from_clipboard_text(text: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_copy_paste() {
+        let mut g: Graph<&str, &str> = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location("a", Pos2::new(0., 0.));
+        let b = g.add_node_with_location("b", Pos2::new(10., 0.));
+        let c = g.add_node("c");
+        g.add_edge(a, b, "ab");
+        g.add_edge(b, c, "bc");
+        g.node_mut(a).unwrap().set_selected(true);
+        g.node_mut(b).unwrap().set_selected(true);
+
+        let sub = Subgraph::from_selection(&g).unwrap();
+        assert_eq!(sub.origin, Pos2::new(5., 0.));
+        assert_eq!(sub.edges.len(), 1);
+
+        let (nodes, edges) = sub.paste_into(&mut g, Pos2::new(100., 100.));
+        assert_eq!((g.node_count(), g.edge_count()), (5, 3));
+        assert!(nodes.iter().all(|idx| ![a, b, c].contains(idx)));
+
+        let (source, target) = g.edge_endpoints(edges[0]).unwrap();
+        assert_eq!(*g.node(source).unwrap().payload(), "a");
+        assert_eq!(g.node(source).unwrap().location(), Pos2::new(95., 100.));
+        assert_eq!(g.node(target).unwrap().location(), Pos2::new(105., 100.));
+    }
+
+    #[cfg(feature = "clipboard")]
+    #[test]
+    fn test_clipboard_text() {
+        let mut g: Graph<String, ()> = Graph::new(StableGraph::default());
+        let a = g.add_node("a".to_string());
+        g.add_edge(a, a, ());
+        g.node_mut(a).unwrap().set_selected(true);
+
+        let sub = Subgraph::from_selection(&g).unwrap();
+        let text = sub.to_clipboard_text().unwrap();
+        assert_eq!(Subgraph::from_clipboard_text(&text).unwrap(), sub);
+    }
+}
@@ -2,18 +2,26 @@ use core::panic;
 
 use egui::{
     epaint::{CubicBezierShape, TextShape},
-    Color32, FontFamily, FontId, Pos2, Shape, Stroke, Vec2,
+    Color32, Pos2, Shape, Stroke, Vec2,
 };
 use petgraph::{stable_graph::IndexType, EdgeType};
 
-use crate::{draw::DrawContext, elements::EdgeProps, node_size, DisplayEdge, DisplayNode, Node};
+use crate::{
+    draw::DrawContext, elements::EdgeProps, node_size, DisplayEdge, DisplayNode, Highlight, Node,
+};
 
-use super::edge_shape_builder::{EdgeShapeBuilder, TipProps};
+use super::{
+    edge_shape_builder::{EdgeShapeBuilder, TipProps},
+    label::LabelCache,
+    node::DIMMED_OPACITY,
+};
 
 #[derive(Clone, Debug)]
 pub struct DefaultEdgeShape {
     pub order: usize,
     pub selected: bool,
+    pub hovered: bool,
+    pub highlight: Highlight,
 
     pub width: f32,
     pub tip_size: f32,
@@ -21,6 +29,8 @@ pub struct DefaultEdgeShape {
     pub curve_size: f32,
     pub loop_size: f32,
     pub label_text: String,
+
+    label_cache: LabelCache,
 }
 
 impl<E: Clone> From<EdgeProps<E>> for DefaultEdgeShape {
@@ -28,6 +38,8 @@ impl<E: Clone> From<EdgeProps<E>> for DefaultEdgeShape {
         Self {
             order: edge.order,
             selected: edge.selected,
+            hovered: edge.hovered,
+            highlight: edge.highlight,
             label_text: edge.label,
 
             width: 2.,
@@ -35,6 +47,7 @@ impl<E: Clone> From<EdgeProps<E>> for DefaultEdgeShape {
             tip_angle: std::f32::consts::TAU / 30.,
             curve_size: 20.,
             loop_size: 3.,
+            label_cache: LabelCache::default(),
         }
     }
 }
@@ -71,16 +84,38 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
     ) -> Vec<egui::Shape> {
         let mut res = vec![];
 
-        let label_visible = ctx.style.labels_always || self.selected;
+        let lod = ctx.lod();
+        let label_visible =
+            (ctx.style.labels_always && !lod.hide_labels) || self.selected || self.hovered;
 
         let style = if self.selected {
             ctx.ctx.style().visuals.widgets.active
+        } else if self.hovered || self.highlight == Highlight::Emphasized {
+            ctx.ctx.style().visuals.widgets.hovered
         } else {
             ctx.ctx.style().visuals.widgets.inactive
         };
-        let color = style.fg_stroke.color;
+        let color = if self.highlight == Highlight::Dimmed && !self.selected {
+            style.fg_stroke.color.gamma_multiply(DIMMED_OPACITY)
+        } else {
+            style.fg_stroke.color
+        };
         let stroke = Stroke::new(self.width, color);
 
+        if lod.straight_edges {
+            // loops are too small to be seen at this zoom
+            if start.id() != end.id() {
+                res.push(Shape::line_segment(
+                    [
+                        ctx.meta.canvas_to_screen_pos(start.location()),
+                        ctx.meta.canvas_to_screen_pos(end.location()),
+                    ],
+                    Stroke::new(ctx.meta.canvas_to_screen_size(self.width), color),
+                ));
+            }
+            return res;
+        }
+
         if start.id() == end.id() {
             // draw loop
             let size = node_size(start, Vec2::new(-1., 0.));
@@ -97,13 +132,12 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
 
             // TODO: export to func
             if label_visible {
-                let galley = ctx.ctx.fonts(|f| {
-                    f.layout_no_wrap(
-                        self.label_text.clone(),
-                        FontId::new(ctx.meta.canvas_to_screen_size(size), FontFamily::Monospace),
-                        color,
-                    )
-                });
+                let galley = self.label_cache.galley(
+                    ctx.ctx,
+                    &self.label_text,
+                    ctx.meta.canvas_to_screen_size(size),
+                    color,
+                );
 
                 let flattened_curve = line_looped.flatten(None);
                 let median = *flattened_curve.get(flattened_curve.len() / 2).unwrap();
@@ -142,13 +176,12 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
             // TODO: export to func
             if label_visible {
                 let size = (node_size(start, dir) + node_size(end, dir)) / 2.;
-                let galley = ctx.ctx.fonts(|f| {
-                    f.layout_no_wrap(
-                        self.label_text.clone(),
-                        FontId::new(ctx.meta.canvas_to_screen_size(size), FontFamily::Monospace),
-                        color,
-                    )
-                });
+                let galley = self.label_cache.galley(
+                    ctx.ctx,
+                    &self.label_text,
+                    ctx.meta.canvas_to_screen_size(size),
+                    color,
+                );
 
                 let dist = end_connector_point - start_connector_point;
                 let center = ctx
@@ -188,13 +221,12 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
 
         if label_visible {
             let size = (node_size(start, dir) + node_size(end, dir)) / 2.;
-            let galley = ctx.ctx.fonts(|f| {
-                f.layout_no_wrap(
-                    self.label_text.clone(),
-                    FontId::new(ctx.meta.canvas_to_screen_size(size), FontFamily::Monospace),
-                    color,
-                )
-            });
+            let galley = self.label_cache.galley(
+                ctx.ctx,
+                &self.label_text,
+                ctx.meta.canvas_to_screen_size(size),
+                color,
+            );
 
             let flattened_curve = line_curved.flatten(None);
             let median = *flattened_curve.get(flattened_curve.len() / 2).unwrap();
@@ -213,7 +245,11 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
     fn update(&mut self, state: &EdgeProps<E>) {
         self.order = state.order;
         self.selected = state.selected;
-        self.label_text = state.label.to_string();
+        self.hovered = state.hovered;
+        self.highlight = state.highlight;
+        if self.label_text != state.label {
+            self.label_text.clone_from(&state.label);
+        }
     }
 }
 
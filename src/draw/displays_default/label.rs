@@ -0,0 +1,79 @@
+use std::sync::{Arc, Weak};
+
+use egui::{epaint::TextureAtlas, mutex::Mutex, Color32, Context, FontFamily, FontId, Galley};
+
+/// Step of the font size in screen pixels. Labels are laid out again only when the size
+/// changes by a step, so zooming does not lay them out on every frame.
+const FONT_SIZE_STEP: f32 = 0.5;
+
+/// Laid out label which is kept between frames.
+///
+/// The galley is laid out again when the text, the font size step or the color changes.
+/// It is also dropped when egui recreates the font atlas, e.g. when the scale factor changes,
+/// because the galley refers to the glyphs in the atlas.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LabelCache {
+    text: String,
+    size_step: u32,
+    color: Color32,
+    atlas: Weak<Mutex<TextureAtlas>>,
+    galley: Option<Arc<Galley>>,
+}
+
+impl LabelCache {
+    /// Returns the galley of the text with the given monospace font size in screen pixels.
+    pub fn galley(&mut self, ctx: &Context, text: &str, size: f32, color: Color32) -> Arc<Galley> {
+        let size_step = (size / FONT_SIZE_STEP).round() as u32;
+        ctx.fonts(|f| {
+            let atlas = f.texture_atlas();
+            if let Some(galley) = &self.galley {
+                if self.text == text
+                    && self.size_step == size_step
+                    && self.color == color
+                    && Weak::ptr_eq(&self.atlas, &Arc::downgrade(&atlas))
+                {
+                    return galley.clone();
+                }
+            }
+
+            let galley = f.layout_no_wrap(
+                text.to_string(),
+                FontId::new(size_step as f32 * FONT_SIZE_STEP, FontFamily::Monospace),
+                color,
+            );
+            self.text.clear();
+            self.text.push_str(text);
+            self.size_step = size_step;
+            self.color = color;
+            self.atlas = Arc::downgrade(&atlas);
+            self.galley = Some(galley.clone());
+            galley
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_cache() {
+        let ctx = Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |_| {});
+
+        let mut cache = LabelCache::default();
+        let galley = cache.galley(&ctx, "label", 10., Color32::RED);
+        assert!(Arc::ptr_eq(
+            &galley,
+            &cache.galley(&ctx, "label", 10.1, Color32::RED)
+        ));
+        assert!(!Arc::ptr_eq(
+            &galley,
+            &cache.galley(&ctx, "label", 12., Color32::RED)
+        ));
+        assert!(!Arc::ptr_eq(
+            &cache.galley(&ctx, "label", 12., Color32::RED),
+            &cache.galley(&ctx, "other", 12., Color32::RED)
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 mod edge;
 mod edge_shape_builder;
+mod label;
 mod node;
 
 pub use edge::DefaultEdgeShape;
@@ -1,10 +1,18 @@
 use egui::{
     epaint::{CircleShape, TextShape},
-    Color32, FontFamily, FontId, Pos2, Shape, Stroke, Vec2,
+    Color32, Pos2, Shape, Stroke, Vec2,
 };
 use petgraph::{stable_graph::IndexType, EdgeType};
 
-use crate::{draw::drawer::DrawContext, DisplayNode, NodeProps};
+use crate::{draw::drawer::DrawContext, DisplayNode, Highlight, NodeProps};
+
+use super::label::LabelCache;
+
+/// Opacity of the nodes and edges outside of the highlighted neighborhood.
+pub(super) const DIMMED_OPACITY: f32 = 0.25;
+
+/// Minimal radius in screen pixels of the nodes drawn as points.
+const POINT_RADIUS: f32 = 1.5;
 
 /// This is the default node shape which is used to display nodes in the graph.
 ///
@@ -15,12 +23,17 @@ pub struct DefaultNodeShape {
 
     pub selected: bool,
     pub dragged: bool,
+    pub hovered: bool,
+    pub focused: bool,
+    pub highlight: Highlight,
     pub color: Option<Color32>,
 
     pub label_text: String,
 
     /// Shape dependent property
     pub radius: f32,
+
+    label_cache: LabelCache,
 }
 
 impl<N: Clone> From<NodeProps<N>> for DefaultNodeShape {
@@ -29,10 +42,14 @@ impl<N: Clone> From<NodeProps<N>> for DefaultNodeShape {
             pos: node_props.location(),
             selected: node_props.selected,
             dragged: node_props.dragged,
+            hovered: node_props.hovered,
+            focused: node_props.focused,
+            highlight: node_props.highlight,
             label_text: node_props.label.to_string(),
             color: node_props.color(),
 
             radius: 5.0,
+            label_cache: LabelCache::default(),
         }
     }
 }
@@ -55,38 +72,56 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType> DisplayNode<N, E, Ty, Ix>
 
         let style = if is_interacted {
             ctx.ctx.style().visuals.widgets.active
+        } else if self.hovered || self.highlight == Highlight::Emphasized {
+            ctx.ctx.style().visuals.widgets.hovered
         } else {
             ctx.ctx.style().visuals.widgets.inactive
         };
 
-        let color = if let Some(c) = self.color {
+        let mut color = if let Some(c) = self.color {
             c
         } else {
             style.fg_stroke.color
         };
+        if self.highlight == Highlight::Dimmed && !is_interacted {
+            color = color.gamma_multiply(DIMMED_OPACITY);
+        }
 
+        let lod = ctx.lod();
         let circle_center = ctx.meta.canvas_to_screen_pos(self.pos);
         let circle_radius = ctx.meta.canvas_to_screen_size(self.radius);
+        if lod.point_nodes {
+            res.push(Shape::circle_filled(
+                circle_center,
+                circle_radius.max(POINT_RADIUS),
+                color,
+            ));
+            return res;
+        }
+
         let circle_shape = CircleShape {
             center: circle_center,
             radius: circle_radius,
             fill: color,
-            stroke: Stroke::default(),
+            stroke: if self.focused {
+                ctx.ctx.style().visuals.selection.stroke
+            } else {
+                Stroke::default()
+            },
         };
         res.push(circle_shape.into());
 
-        let label_visible = ctx.style.labels_always || self.selected || self.dragged;
+        let label_visible = (ctx.style.labels_always && !lod.hide_labels)
+            || is_interacted
+            || self.hovered
+            || self.highlight == Highlight::Emphasized;
         if !label_visible {
             return res;
         }
 
-        let galley = ctx.ctx.fonts(|f| {
-            f.layout_no_wrap(
-                self.label_text.clone(),
-                FontId::new(circle_radius, FontFamily::Monospace),
-                color,
-            )
-        });
+        let galley = self
+            .label_cache
+            .galley(ctx.ctx, &self.label_text, circle_radius, color);
 
         // display label centered over the circle
         let label_pos = Pos2::new(
@@ -104,7 +139,12 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType> DisplayNode<N, E, Ty, Ix>
         self.pos = state.location();
         self.selected = state.selected;
         self.dragged = state.dragged;
-        self.label_text = state.label.to_string();
+        self.hovered = state.hovered;
+        self.focused = state.focused;
+        self.highlight = state.highlight;
+        if self.label_text != state.label {
+            self.label_text.clone_from(&state.label);
+        }
         self.color = state.color();
     }
 }
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use egui::{Context, Painter, Shape};
+use egui::{Context, Painter, Rect, Shape};
 use petgraph::graph::IndexType;
 use petgraph::EdgeType;
 
@@ -12,6 +12,22 @@ use crate::{
 
 use super::{DisplayEdge, DisplayNode};
 
+/// Margin in screen pixels around the widget in which elements are still drawn, so the parts
+/// of the elements which are just outside of the widget, like labels, are not cut.
+const CULL_MARGIN: f32 = 100.;
+
+/// Simplifications of the elements at the current zoom level,
+/// see [`SettingsStyle::with_lod_labels_zoom`] and related settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelOfDetail {
+    /// Labels are shown only for interacted elements.
+    pub hide_labels: bool,
+    /// Nodes are drawn as points.
+    pub point_nodes: bool,
+    /// Edges are drawn as straight lines without tips and labels.
+    pub straight_edges: bool,
+}
+
 /// Contains all the data about current widget state which is needed for custom drawing functions.
 pub struct DrawContext<'a> {
     pub ctx: &'a Context,
@@ -21,6 +37,18 @@ pub struct DrawContext<'a> {
     pub meta: &'a Metadata,
 }
 
+impl DrawContext<'_> {
+    /// Returns the level of detail for the current zoom.
+    pub fn lod(&self) -> LevelOfDetail {
+        let zoom = self.meta.zoom;
+        LevelOfDetail {
+            hide_labels: zoom < self.style.lod_labels_zoom,
+            point_nodes: zoom < self.style.lod_nodes_zoom,
+            straight_edges: zoom < self.style.lod_edges_zoom,
+        }
+    }
+}
+
 pub struct Drawer<'a, N, E, Ty, Ix, Nd, Ed, S, L>
 where
     N: Clone,
@@ -60,30 +88,44 @@ where
     }
 
     pub fn draw(mut self) {
-        self.draw_edges();
-        self.draw_nodes();
+        let view = self.view();
+        self.draw_edges(view);
+        self.draw_nodes(view);
         self.draw_postponed();
     }
 
+    /// Returns the visible area in canvas coordinates.
+    fn view(&self) -> Rect {
+        let view = self.ctx.painter.clip_rect().expand(CULL_MARGIN);
+        Rect::from_min_max(
+            self.ctx.meta.screen_to_canvas_pos(view.min),
+            self.ctx.meta.screen_to_canvas_pos(view.max),
+        )
+    }
+
     fn draw_postponed(&mut self) {
         self.delayed.iter().for_each(|s| {
             self.ctx.painter.add(s.clone());
         });
     }
 
-    fn draw_nodes(&mut self) {
+    fn draw_nodes(&mut self, view: Rect) {
+        let visible = self.g.nodes_in_rect(view);
         self.g
             .g
             .node_indices()
             .collect::<Vec<_>>()
             .into_iter()
             .for_each(|idx| {
-                let n = self.g.node_mut(idx).unwrap();
-                let props = n.props().clone();
+                // resized nodes are reindexed on the next frame
+                let n = &mut self.g.g[idx];
 
-                let display = n.display_mut();
-                display.update(&props);
-                let shapes = display.shapes(self.ctx);
+                // displays of the culled nodes are updated too as they are used for hit testing
+                n.update_display();
+                if visible.binary_search(&idx).is_err() {
+                    return;
+                }
+                let shapes = n.display_mut().shapes(self.ctx);
 
                 if n.selected() || n.dragged() {
                     for s in shapes {
@@ -97,35 +139,34 @@ where
             });
     }
 
-    fn draw_edges(&mut self) {
-        self.g
-            .g
-            .edge_indices()
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|idx| {
-                let (idx_start, idx_end) = self.g.edge_endpoints(idx).unwrap();
-
-                // FIXME: not a good decision to clone nodes for every edge
-                let start = self.g.node(idx_start).cloned().unwrap();
-                let end = self.g.node(idx_end).cloned().unwrap();
-
-                let e = self.g.edge_mut(idx).unwrap();
-                let props = e.props().clone();
-
-                let display = e.display_mut();
-                display.update(&props);
-                let shapes = display.shapes(&start, &end, self.ctx);
-
-                if e.selected() {
-                    for s in shapes {
-                        self.delayed.push(s);
-                    }
-                } else {
-                    for s in shapes {
-                        self.ctx.painter.add(s);
-                    }
-                }
-            });
+    fn draw_edges(&mut self, view: Rect) {
+        let visible = self.g.edges_in_rect(view);
+        let indices = self.g.g.edge_indices().collect::<Vec<_>>();
+
+        // The drawn edge display is swapped with this one, so it can be borrowed mutably
+        // while the endpoint nodes are borrowed from the graph without cloning them.
+        let Some(mut display) = indices.first().map(|idx| self.g.g[*idx].display().clone()) else {
+            return;
+        };
+
+        for idx in indices {
+            let e = &mut self.g.g[idx];
+            e.update_display();
+            if visible.binary_search(&idx).is_err() {
+                continue;
+            }
+
+            std::mem::swap(&mut display, e.display_mut());
+            let (idx_start, idx_end) = self.g.edge_endpoints(idx).unwrap();
+            let shapes = display.shapes(&self.g.g[idx_start], &self.g.g[idx_end], self.ctx);
+            let e = &mut self.g.g[idx];
+            std::mem::swap(&mut display, e.display_mut());
+
+            if e.selected() {
+                self.delayed.extend(shapes);
+            } else {
+                self.ctx.painter.extend(shapes);
+            }
+        }
     }
 }
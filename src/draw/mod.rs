@@ -5,4 +5,4 @@ mod drawer;
 pub use displays::{DisplayEdge, DisplayNode};
 pub use displays_default::DefaultEdgeShape;
 pub use displays_default::DefaultNodeShape;
-pub use drawer::{DrawContext, Drawer};
+pub use drawer::{DrawContext, Drawer, LevelOfDetail};
@@ -6,7 +6,7 @@ use petgraph::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode};
+use crate::{DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, Highlight};
 
 /// Stores properties of an [Edge]
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -15,10 +15,16 @@ pub struct EdgeProps<E: Clone> {
     pub order: usize,
     pub selected: bool,
     pub label: String,
+    /// Whether the pointer is over the edge. It is updated by [`crate::GraphView`] every frame.
+    #[serde(skip)]
+    pub hovered: bool,
+    /// Whether the edge is in the highlighted neighborhood. It is updated by [`crate::GraphView`] every frame.
+    #[serde(skip)]
+    pub highlight: Highlight,
 }
 
 /// Stores properties of an edge that can be changed. Used to apply changes to the graph.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Edge<
     N: Clone,
     E: Clone,
@@ -35,6 +41,25 @@ pub struct Edge<
     _marker: PhantomData<(N, Ty, Dn)>,
 }
 
+impl<
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        D: DisplayEdge<N, E, Ty, Ix, Dn>,
+    > Clone for Edge<N, E, Ty, Ix, Dn, D>
+{
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            display: self.display.clone(),
+            props: self.props.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<
         N: Clone,
         E: Clone,
@@ -51,6 +76,8 @@ impl<
             order: usize::default(),
             selected: bool::default(),
             label: String::default(),
+            hovered: bool::default(),
+            highlight: Highlight::default(),
         };
 
         let display = D::from(props.clone());
@@ -67,6 +94,10 @@ impl<
         &self.props
     }
 
+    pub(crate) fn props_mut(&mut self) -> &mut EdgeProps<E> {
+        &mut self.props
+    }
+
     pub fn display(&self) -> &D {
         &self.display
     }
@@ -75,6 +106,11 @@ impl<
         &mut self.display
     }
 
+    /// Updates the display with the current props without cloning them.
+    pub(crate) fn update_display(&mut self) {
+        self.display.update(&self.props);
+    }
+
     #[allow(clippy::missing_panics_doc)] // TODO: Add panic message
     pub fn id(&self) -> EdgeIndex<Ix> {
         self.id.unwrap()
@@ -108,6 +144,22 @@ impl<
         self.props.selected
     }
 
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.props.hovered = hovered;
+    }
+
+    pub fn hovered(&self) -> bool {
+        self.props.hovered
+    }
+
+    pub fn set_highlight(&mut self, highlight: Highlight) {
+        self.props.highlight = highlight;
+    }
+
+    pub fn highlight(&self) -> Highlight {
+        self.props.highlight
+    }
+
     pub fn set_label(&mut self, label: String) {
         self.props.label = label;
     }
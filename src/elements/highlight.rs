@@ -0,0 +1,12 @@
+/// Emphasis of a node or an edge when neighborhood highlighting is enabled
+/// with [`crate::SettingsStyle::with_highlight_neighbors_enabled`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Highlight {
+    /// Nothing is highlighted.
+    #[default]
+    None,
+    /// Element belongs to the highlighted neighborhood.
+    Emphasized,
+    /// Element is outside of the highlighted neighborhood.
+    Dimmed,
+}
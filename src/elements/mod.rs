@@ -1,5 +1,7 @@
 mod edge;
+mod highlight;
 mod node;
 
 pub use edge::{Edge, EdgeProps};
+pub use highlight::Highlight;
 pub use node::{Node, NodeProps};
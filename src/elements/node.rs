@@ -8,7 +8,7 @@ use petgraph::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{DefaultNodeShape, DisplayNode};
+use crate::{DefaultNodeShape, DisplayNode, Highlight};
 
 /// Stores properties of a [Node]
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -20,10 +20,19 @@ where
     pub label: String,
     pub selected: bool,
     pub dragged: bool,
-
-    color: Option<Color32>,
-    location: Pos2,
-    location_user: Option<Pos2>,
+    /// Whether the pointer is over the node. It is updated by [`crate::GraphView`] every frame.
+    #[serde(skip)]
+    pub hovered: bool,
+    /// Whether the node has keyboard focus. It is updated by [`crate::GraphView`].
+    #[serde(skip)]
+    pub focused: bool,
+    /// Whether the node is in the highlighted neighborhood. It is updated by [`crate::GraphView`] every frame.
+    #[serde(skip)]
+    pub highlight: Highlight,
+
+    pub(crate) color: Option<Color32>,
+    pub(crate) location: Pos2,
+    pub(crate) location_user: Option<Pos2>,
 }
 
 impl<N> NodeProps<N>
@@ -107,6 +116,9 @@ where
             label: String::default(),
             selected: bool::default(),
             dragged: bool::default(),
+            hovered: bool::default(),
+            focused: bool::default(),
+            highlight: Highlight::default(),
         };
 
         Node::new_with_props(props)
@@ -128,6 +140,10 @@ where
         &self.props
     }
 
+    pub(crate) fn props_mut(&mut self) -> &mut NodeProps<N> {
+        &mut self.props
+    }
+
     pub fn display(&self) -> &D {
         &self.display
     }
@@ -136,6 +152,11 @@ where
         &mut self.display
     }
 
+    /// Updates the display with the current props without cloning them.
+    pub(crate) fn update_display(&mut self) {
+        self.display.update(&self.props);
+    }
+
     #[allow(clippy::missing_panics_doc)] // TODO: Add panic message
     pub fn id(&self) -> NodeIndex<Ix> {
         self.id.unwrap()
@@ -182,6 +203,30 @@ where
         self.props.selected = selected;
     }
 
+    pub fn hovered(&self) -> bool {
+        self.props.hovered
+    }
+
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.props.hovered = hovered;
+    }
+
+    pub fn focused(&self) -> bool {
+        self.props.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.props.focused = focused;
+    }
+
+    pub fn highlight(&self) -> Highlight {
+        self.props.highlight
+    }
+
+    pub fn set_highlight(&mut self, highlight: Highlight) {
+        self.props.highlight = highlight;
+    }
+
     pub fn dragged(&self) -> bool {
         self.props.dragged
     }
@@ -54,6 +54,22 @@ pub struct PayloadEdgeClick {
     pub id: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeSecondaryClick {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeSecondaryClick {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadCanvasSecondaryClick {
+    /// Click position in the canvas coordinates
+    pub pos: [f32; 2],
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PayloadEdgeSelect {
     pub id: usize,
@@ -64,6 +80,73 @@ pub struct PayloadEdgeDeselect {
     pub id: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeCreateRequest {
+    pub source: usize,
+    pub target: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeCreate {
+    pub id: usize,
+    pub source: usize,
+    pub target: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeCreate {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeRemove {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeRemove {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeReconnect {
+    pub id: usize,
+    pub source: usize,
+    pub target: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeLabelChange {
+    pub id: usize,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeLabelChange {
+    pub id: usize,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeHoverEnter {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeHoverLeave {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeHoverEnter {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeHoverLeave {
+    pub id: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Event {
     Pan(PayloadPan),
@@ -78,4 +161,24 @@ pub enum Event {
     EdgeClick(PayloadEdgeClick),
     EdgeSelect(PayloadEdgeSelect),
     EdgeDeselect(PayloadEdgeDeselect),
+    /// User has drawn an edge from the source node to the target node.
+    EdgeCreateRequest(PayloadEdgeCreateRequest),
+    /// Edge is added to the graph by the edge factory, undo or redo.
+    EdgeCreate(PayloadEdgeCreate),
+    NodeCreate(PayloadNodeCreate),
+    /// Node is removed together with all its edges.
+    NodeRemove(PayloadNodeRemove),
+    EdgeRemove(PayloadEdgeRemove),
+    /// Edge is moved to connect other nodes. Its index can change.
+    EdgeReconnect(PayloadEdgeReconnect),
+    NodeLabelChange(PayloadNodeLabelChange),
+    EdgeLabelChange(PayloadEdgeLabelChange),
+    NodeHoverEnter(PayloadNodeHoverEnter),
+    NodeHoverLeave(PayloadNodeHoverLeave),
+    EdgeHoverEnter(PayloadEdgeHoverEnter),
+    EdgeHoverLeave(PayloadEdgeHoverLeave),
+    NodeSecondaryClick(PayloadNodeSecondaryClick),
+    EdgeSecondaryClick(PayloadEdgeSecondaryClick),
+    /// Secondary click on the empty space of the canvas.
+    CanvasSecondaryClick(PayloadCanvasSecondaryClick),
 }
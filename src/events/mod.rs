@@ -1,7 +1,11 @@
 mod event;
 
 pub use event::{
-    Event, PayloadEdgeClick, PayloadEdgeDeselect, PayloadEdgeSelect, PayloadNodeClick,
-    PayloadNodeDeselect, PayloadNodeDoubleClick, PayloadNodeDragEnd, PayloadNodeDragStart,
-    PayloadNodeMove, PayloadNodeSelect, PayloadPan, PayloadZoom,
+    Event, PayloadCanvasSecondaryClick, PayloadEdgeClick, PayloadEdgeCreate,
+    PayloadEdgeCreateRequest, PayloadEdgeDeselect, PayloadEdgeHoverEnter, PayloadEdgeHoverLeave,
+    PayloadEdgeLabelChange, PayloadEdgeReconnect, PayloadEdgeRemove, PayloadEdgeSecondaryClick,
+    PayloadEdgeSelect, PayloadNodeClick, PayloadNodeCreate, PayloadNodeDeselect,
+    PayloadNodeDoubleClick, PayloadNodeDragEnd, PayloadNodeDragStart, PayloadNodeHoverEnter,
+    PayloadNodeHoverLeave, PayloadNodeLabelChange, PayloadNodeMove, PayloadNodeRemove,
+    PayloadNodeSecondaryClick, PayloadNodeSelect, PayloadPan, PayloadZoom,
 };
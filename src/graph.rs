@@ -1,8 +1,11 @@
-use egui::Pos2;
+use std::collections::HashSet;
+
+use egui::{Color32, Pos2, Rect, Vec2};
 use petgraph::stable_graph::DefaultIx;
 use petgraph::Directed;
 
 use petgraph::graph::IndexType;
+use petgraph::matrix_graph::{MatrixGraph, Nullable};
 use petgraph::{
     stable_graph::{EdgeIndex, EdgeReference, NodeIndex, StableGraph},
     visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences},
@@ -11,8 +14,13 @@ use petgraph::{
 use serde::{Deserialize, Serialize};
 
 use crate::draw::{DisplayEdge, DisplayNode};
-use crate::{metadata::Metadata, Edge, Node};
-use crate::{to_graph, DefaultEdgeShape, DefaultNodeShape};
+use crate::{from_matrix_graph, to_graph, DefaultEdgeShape, DefaultNodeShape};
+use crate::{
+    history::{apply_step, Change, History, Step},
+    metadata::Metadata,
+    spatial::{edge_rect, node_rect, SpatialIndex},
+    Edge, HighlightDirection, Node,
+};
 
 type StableGraphType<N, E, Ty, Ix, Dn, De> =
     StableGraph<Node<N, E, Ty, Ix, Dn>, Edge<N, E, Ty, Ix, Dn, De>, Ty, Ix>;
@@ -39,6 +47,16 @@ pub struct Graph<
     selected_nodes: Vec<NodeIndex<Ix>>,
     selected_edges: Vec<EdgeIndex<Ix>>,
     dragged_node: Option<NodeIndex<Ix>>,
+    hovered_node: Option<NodeIndex<Ix>>,
+    hovered_edge: Option<EdgeIndex<Ix>>,
+    focused_node: Option<NodeIndex<Ix>>,
+    highlighted_nodes: Vec<NodeIndex<Ix>>,
+    highlighted_edges: Vec<EdgeIndex<Ix>>,
+
+    #[serde(skip, default = "History::default")]
+    pub(crate) history: History<N, E, Ty, Ix, Dn, De>,
+    #[serde(skip, default = "SpatialIndex::default")]
+    spatial: SpatialIndex<Ix>,
 }
 
 impl<N, E, Ty, Ix, Dn, De> From<&StableGraph<N, E, Ty, Ix>> for Graph<N, E, Ty, Ix, Dn, De>
@@ -55,6 +73,22 @@ where
     }
 }
 
+impl<N, E, Ty, Null, Ix, Dn, De> From<&MatrixGraph<N, E, Ty, Null, Ix>>
+    for Graph<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Null: Nullable<Wrapped = E>,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    fn from(g: &MatrixGraph<N, E, Ty, Null, Ix>) -> Self {
+        from_matrix_graph(g)
+    }
+}
+
 impl<N, E, Ty, Ix, Dn, De> Graph<N, E, Ty, Ix, Dn, De>
 where
     N: Clone,
@@ -70,40 +104,212 @@ where
             selected_nodes: Vec::default(),
             selected_edges: Vec::default(),
             dragged_node: Option::default(),
+            hovered_node: Option::default(),
+            hovered_edge: Option::default(),
+            focused_node: Option::default(),
+            highlighted_nodes: Vec::default(),
+            highlighted_edges: Vec::default(),
+            history: History::default(),
+            spatial: SpatialIndex::default(),
         }
     }
 
-    /// Finds node by position. Can be optimized by using a spatial index like quad-tree if needed.
+    /// Finds node by position.
     pub fn node_by_screen_pos(&self, meta: &Metadata, screen_pos: Pos2) -> Option<NodeIndex<Ix>> {
         let pos_in_graph = meta.screen_to_canvas_pos(screen_pos);
-        for (idx, node) in self.nodes_iter() {
-            let display = node.display();
-            if display.is_inside(pos_in_graph) {
-                return Some(idx);
-            }
-        }
-        None
+        self.nodes_in_rect(Rect::from_min_max(pos_in_graph, pos_in_graph))
+            .into_iter()
+            .find(|idx| {
+                self.node(*idx)
+                    .is_some_and(|n| n.display().is_inside(pos_in_graph))
+            })
     }
 
     /// Finds edge by position.
-    #[allow(clippy::missing_panics_doc)] // TODO: add panics doc
     pub fn edge_by_screen_pos(&self, meta: &Metadata, screen_pos: Pos2) -> Option<EdgeIndex<Ix>> {
         let pos_in_graph = meta.screen_to_canvas_pos(screen_pos);
-        for (idx, e) in self.edges_iter() {
-            let Some((idx_start, idx_end)) = self.g.edge_endpoints(e.id()) else {
-                continue;
-            };
-            let start = self.g.node_weight(idx_start).unwrap();
-            let end = self.g.node_weight(idx_end).unwrap();
-            if e.display().is_inside(start, end, pos_in_graph) {
-                return Some(idx);
+        self.edges_in_rect(Rect::from_min_max(pos_in_graph, pos_in_graph))
+            .into_iter()
+            .find(|idx| {
+                let Some((idx_start, idx_end)) = self.g.edge_endpoints(*idx) else {
+                    return false;
+                };
+                let (Some(start), Some(end), Some(e)) =
+                    (self.node(idx_start), self.node(idx_end), self.edge(*idx))
+                else {
+                    return false;
+                };
+                e.display().is_inside(start, end, pos_in_graph)
+            })
+    }
+
+    /// Finds the node which center is the closest to the position in canvas coordinates
+    /// and not farther than `max_distance`.
+    pub fn nearest_node(&self, pos: Pos2, max_distance: f32) -> Option<NodeIndex<Ix>> {
+        let area = Rect::from_center_size(pos, Vec2::splat(2. * max_distance));
+        self.nodes_in_rect(area)
+            .into_iter()
+            .filter_map(|idx| Some((idx, self.node(idx)?.location().distance(pos))))
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Returns nodes which bounds intersect the rect in canvas coordinates, sorted by index.
+    pub fn nodes_in_rect(&self, rect: Rect) -> Vec<NodeIndex<Ix>> {
+        let spatial = &self.spatial;
+        let intersects = |idx: &NodeIndex<Ix>| {
+            self.node(*idx)
+                .is_some_and(|n| node_rect(n).intersects(rect))
+        };
+        if spatial.stale {
+            return self.g.node_indices().filter(intersects).collect();
+        }
+
+        // nodes changed since the last update are checked by their current bounds
+        let mut found = spatial
+            .nodes
+            .query(rect)
+            .into_iter()
+            .filter(|idx| !spatial.dirty_nodes.contains(idx))
+            .chain(spatial.dirty_nodes.iter().copied().filter(intersects))
+            .collect::<Vec<_>>();
+        found.sort_unstable();
+        found
+    }
+
+    /// Returns edges which approximate bounds intersect the rect in canvas coordinates,
+    /// sorted by index.
+    pub fn edges_in_rect(&self, rect: Rect) -> Vec<EdgeIndex<Ix>> {
+        let spatial = &self.spatial;
+        let intersects =
+            |idx: &EdgeIndex<Ix>| self.edge_bounds(*idx).is_some_and(|r| r.intersects(rect));
+        if spatial.stale {
+            return self.g.edge_indices().filter(intersects).collect();
+        }
+
+        // edges changed since the last update are checked by their current bounds
+        let mut found = spatial
+            .edges
+            .query(rect)
+            .into_iter()
+            .filter(|idx| !spatial.dirty_edges.contains(idx))
+            .chain(spatial.dirty_edges.iter().copied().filter(intersects))
+            .collect::<Vec<_>>();
+        found.sort_unstable();
+        found
+    }
+
+    /// Approximate bounds of the edge in canvas coordinates.
+    fn edge_bounds(&self, idx: EdgeIndex<Ix>) -> Option<Rect> {
+        let (start, end) = self.g.edge_endpoints(idx)?;
+        let (start, end) = (self.node(start)?, self.node(end)?);
+        Some(edge_rect(
+            node_rect(start),
+            node_rect(end),
+            self.edge(idx)?.order(),
+        ))
+    }
+
+    /// Updates the spatial index used for hit testing, box selection and culling.
+    ///
+    /// Only the elements changed through the graph methods since the last update are
+    /// reindexed, until then queries check them by their current bounds. Changes made
+    /// directly to [`Graph::g`] are not tracked, call [`Graph::mark_spatial_index_dirty`]
+    /// after them to rebuild the index. [`super::GraphView`] updates the index once per frame
+    /// and finds nodes moved directly, e.g. by layouts, by itself.
+    pub fn update_spatial_index(&mut self) {
+        if !self.spatial.stale {
+            let nodes = std::mem::take(&mut self.spatial.dirty_nodes);
+            for idx in nodes {
+                match self.g.node_weight(idx) {
+                    Some(n) => {
+                        self.spatial.nodes.insert(idx, node_rect(n));
+                    }
+                    None => self.spatial.nodes.remove(idx),
+                }
+            }
+            let edges = std::mem::take(&mut self.spatial.dirty_edges);
+            for idx in edges {
+                match self.edge_bounds(idx) {
+                    Some(rect) => {
+                        self.spatial.edges.insert(idx, rect);
+                    }
+                    None => self.spatial.edges.remove(idx),
+                }
             }
+
+            // elements added or removed directly are found by the count
+            self.spatial.stale = self.spatial.nodes.len() != self.g.node_count()
+                || self.spatial.edges.len() != self.g.edge_count();
+        }
+        if !self.spatial.stale {
+            return;
+        }
+
+        self.spatial.nodes.clear();
+        for idx in self.g.node_indices() {
+            self.spatial.nodes.insert(idx, node_rect(&self.g[idx]));
+        }
+        self.spatial.edges.clear();
+        for idx in self.g.edge_indices() {
+            if let Some(rect) = self.edge_bounds(idx) {
+                self.spatial.edges.insert(idx, rect);
+            }
+        }
+        self.spatial.dirty_nodes.clear();
+        self.spatial.dirty_edges.clear();
+        self.spatial.stale = false;
+    }
+
+    /// Marks the whole spatial index as outdated, so it is not used until it is rebuilt by
+    /// [`Graph::update_spatial_index`]. Call it after changing node locations or the graph
+    /// directly through [`Graph::g`].
+    pub fn mark_spatial_index_dirty(&mut self) {
+        self.spatial.stale = true;
+    }
+
+    /// Marks the node and its edges to be reindexed.
+    pub(crate) fn mark_node_dirty(&mut self, idx: NodeIndex<Ix>) {
+        if self.spatial.stale {
+            return;
+        }
+        self.spatial.dirty_nodes.insert(idx);
+        self.spatial.dirty_edges.extend(
+            self.g
+                .edges_directed(idx, Direction::Outgoing)
+                .chain(self.g.edges_directed(idx, Direction::Incoming))
+                .map(|e| e.id()),
+        );
+    }
+
+    /// Marks the edge to be reindexed.
+    pub(crate) fn mark_edge_dirty(&mut self, idx: EdgeIndex<Ix>) {
+        if self.spatial.stale {
+            return;
         }
+        self.spatial.dirty_edges.insert(idx);
+    }
 
-        None
+    /// Marks the nodes which bounds differ from the indexed ones, e.g. moved directly
+    /// through [`Graph::g`] or resized by their displays.
+    pub(crate) fn mark_moved_nodes(&mut self) {
+        if self.spatial.stale {
+            return;
+        }
+        let moved = self
+            .g
+            .node_indices()
+            .filter(|idx| self.spatial.nodes.rect(*idx) != Some(node_rect(&self.g[*idx])))
+            .collect::<Vec<_>>();
+        for idx in moved {
+            self.mark_node_dirty(idx);
+        }
     }
 
+    /// Returns the underlying graph. The spatial index is rebuilt after changes made through it.
     pub fn g(&mut self) -> &mut StableGraphType<N, E, Ty, Ix, Dn, De> {
+        self.mark_spatial_index_dirty();
         &mut self.g
     }
 
@@ -119,6 +325,9 @@ where
         graph_node.set_location(Pos2::default());
         graph_node.set_label(idx.index().to_string());
 
+        self.mark_node_dirty(idx);
+        self.history.record(|| Change::RemoveNode(idx));
+
         idx
     }
 
@@ -134,6 +343,9 @@ where
         graph_node.set_location(location);
         graph_node.set_label(idx.index().to_string());
 
+        self.mark_node_dirty(idx);
+        self.history.record(|| Change::RemoveNode(idx));
+
         idx
     }
 
@@ -159,11 +371,16 @@ where
         graph_node.set_location(location);
         graph_node.set_label(label);
 
+        self.mark_node_dirty(idx);
+        self.history.record(|| Change::RemoveNode(idx));
+
         idx
     }
 
     /// Removes node by index. Returns removed node and None if it does not exist.
     pub fn remove_node(&mut self, idx: NodeIndex<Ix>) -> Option<Node<N, E, Ty, Ix, Dn>> {
+        self.history.begin_group();
+
         // before removing nodes we need to remove all edges connected to it
         let neighbors = self.g.neighbors_undirected(idx).collect::<Vec<_>>();
         for n in &neighbors {
@@ -171,7 +388,14 @@ where
             self.remove_edges_between(*n, idx);
         }
 
-        self.g.remove_node(idx)
+        self.mark_node_dirty(idx);
+        let node = self.g.remove_node(idx);
+        if let Some(node) = &node {
+            self.history.record(|| Change::AddNode(idx, node.clone()));
+        }
+
+        self.history.end_group();
+        node
     }
 
     /// Removes all edges between start and end node. Returns removed edges count.
@@ -188,7 +412,7 @@ where
 
         let mut removed = 0;
         for e in &idxs {
-            self.g.remove_edge(*e).unwrap();
+            self.remove_edge(*e).unwrap();
             removed += 1;
         }
 
@@ -212,6 +436,9 @@ where
         e.set_order(order);
         e.set_label(format!("edge {}", e.id().index()));
 
+        self.mark_edge_dirty(idx);
+        self.history.record(|| Change::RemoveEdge(idx));
+
         idx
     }
 
@@ -233,6 +460,9 @@ where
         e.set_order(order);
         e.set_label(label);
 
+        self.mark_edge_dirty(idx);
+        self.history.record(|| Change::RemoveEdge(idx));
+
         idx
     }
 
@@ -243,6 +473,7 @@ where
         let order = self.g.edge_weight(idx)?.order();
 
         let payload = self.g.remove_edge(idx)?;
+        self.mark_edge_dirty(idx);
 
         let siblings = self
             .g
@@ -257,11 +488,206 @@ where
                 continue;
             }
             self.g.edge_weight_mut(*s_idx)?.set_order(sibling_order - 1);
+            self.mark_edge_dirty(*s_idx);
         }
 
+        self.history
+            .record(|| Change::AddEdge(idx, start, end, payload.clone()));
+
         Some(payload)
     }
 
+    /// Moves the edge to connect start and end node keeping its payload, label and state.
+    /// Returns new index of the edge and None if the edge or the nodes do not exist.
+    pub fn reconnect_edge(
+        &mut self,
+        idx: EdgeIndex<Ix>,
+        start: NodeIndex<Ix>,
+        end: NodeIndex<Ix>,
+    ) -> Option<EdgeIndex<Ix>> {
+        if !self.g.contains_node(start) || !self.g.contains_node(end) {
+            return None;
+        }
+        let (old_start, old_end) = self.g.edge_endpoints(idx)?;
+
+        let new_idx = self.without_history(|g| {
+            let mut edge = g.remove_edge(idx)?;
+            edge.set_order(g.g.edges_connecting(start, end).count());
+
+            let new_idx = g.g.add_edge(start, end, edge);
+            g.g.edge_weight_mut(new_idx)?.set_id(new_idx);
+            g.mark_edge_dirty(new_idx);
+            Some(new_idx)
+        })?;
+
+        self.history
+            .record(|| Change::ReconnectEdge(new_idx, old_start, old_end));
+
+        Some(new_idx)
+    }
+
+    /// Sets label of the node recording the change in the history.
+    pub fn set_node_label(&mut self, idx: NodeIndex<Ix>, label: String) {
+        self.update_node(idx, |n| n.set_label(label));
+    }
+
+    /// Sets color of the node recording the change in the history.
+    pub fn set_node_color(&mut self, idx: NodeIndex<Ix>, color: Color32) {
+        self.update_node(idx, |n| n.set_color(color));
+    }
+
+    /// Sets payload of the node recording the change in the history.
+    pub fn set_node_payload(&mut self, idx: NodeIndex<Ix>, payload: N) {
+        self.update_node(idx, |n| *n.payload_mut() = payload);
+    }
+
+    /// Sets label of the edge recording the change in the history.
+    pub fn set_edge_label(&mut self, idx: EdgeIndex<Ix>, label: String) {
+        self.update_edge(idx, |e| e.set_label(label));
+    }
+
+    /// Sets payload of the edge recording the change in the history.
+    pub fn set_edge_payload(&mut self, idx: EdgeIndex<Ix>, payload: E) {
+        self.update_edge(idx, |e| *e.payload_mut() = payload);
+    }
+
+    /// Enables recording of the graph changes for [`Graph::undo`] and [`Graph::redo`].
+    ///
+    /// Recorded are nodes and edges added or removed with the graph methods, changes made with
+    /// `set_node_*` and `set_edge_*` methods, and moves done in [`super::GraphView`]. Selection
+    /// is not recorded, so undo does not take away the selection made after an edit.
+    /// Changes made directly to [`Graph::g`] or with `node_mut` and `edge_mut` are not recorded
+    /// and can make the history invalid, clear it with [`Graph::clear_history`] after such changes.
+    ///
+    /// Disabling the history clears it.
+    pub fn set_history_enabled(&mut self, enabled: bool) {
+        self.history.set_enabled(enabled);
+    }
+
+    pub fn history_enabled(&self) -> bool {
+        self.history.enabled()
+    }
+
+    /// Sets maximum number of the undo steps. Default is 100.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Reverts the last recorded step. Returns false if there is nothing to undo.
+    ///
+    /// If the history is out of sync with the graph, e.g. an index of a removed node is taken
+    /// by a node added directly to [`Graph::g`], the graph is not changed, the history is cleared
+    /// and false is returned.
+    pub fn undo(&mut self) -> bool {
+        let Some(step) = self.history.pop_undo() else {
+            return false;
+        };
+
+        let Some(redo) = self.apply_history_step(step) else {
+            return false;
+        };
+        self.history.push_redo(redo);
+
+        true
+    }
+
+    /// Repeats the last undone step. Returns false if there is nothing to redo.
+    ///
+    /// The history is handled the same way as in [`Graph::undo`] if it is out of sync with the graph.
+    pub fn redo(&mut self) -> bool {
+        let Some(step) = self.history.pop_redo() else {
+            return false;
+        };
+
+        let Some(undo) = self.apply_history_step(step) else {
+            return false;
+        };
+        self.history.push_undo(undo);
+
+        true
+    }
+
+    /// Applies the step of the history and returns the step which reverts it.
+    /// Clears the history if it is out of sync with the graph.
+    fn apply_history_step(
+        &mut self,
+        step: Step<N, E, Ty, Ix, Dn, De>,
+    ) -> Option<Step<N, E, Ty, Ix, Dn, De>> {
+        let revert = self.without_history(|g| apply_step(g, step));
+        if revert.is_none() {
+            self.history.clear();
+        }
+        self.sync_selection();
+
+        revert
+    }
+
+    fn update_node(&mut self, idx: NodeIndex<Ix>, f: impl FnOnce(&mut Node<N, E, Ty, Ix, Dn>)) {
+        self.mark_node_dirty(idx);
+        let Some(n) = self.g.node_weight_mut(idx) else {
+            return;
+        };
+
+        let props = self.history.enabled().then(|| n.props().clone());
+        f(n);
+        if let Some(props) = props {
+            self.history.record(|| Change::NodeProps(idx, props));
+        }
+    }
+
+    fn update_edge(&mut self, idx: EdgeIndex<Ix>, f: impl FnOnce(&mut Edge<N, E, Ty, Ix, Dn, De>)) {
+        self.mark_edge_dirty(idx);
+        let Some(e) = self.g.edge_weight_mut(idx) else {
+            return;
+        };
+
+        let props = self.history.enabled().then(|| e.props().clone());
+        f(e);
+        if let Some(props) = props {
+            self.history.record(|| Change::EdgeProps(idx, props));
+        }
+    }
+
+    /// Runs the function without recording changes in the history.
+    fn without_history<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let history = std::mem::take(&mut self.history);
+        let res = f(self);
+        self.history = history;
+        res
+    }
+
+    /// Updates selected nodes and edges after changes made outside of [`super::GraphView`].
+    fn sync_selection(&mut self) {
+        self.selected_nodes = self
+            .nodes_iter()
+            .filter(|(_, n)| n.selected())
+            .map(|(idx, _)| idx)
+            .collect();
+        self.selected_edges = self
+            .edges_iter()
+            .filter(|(_, e)| e.selected())
+            .map(|(idx, _)| idx)
+            .collect();
+        if self
+            .dragged_node
+            .is_some_and(|idx| self.g.node_weight(idx).is_none())
+        {
+            self.dragged_node = None;
+        }
+    }
+
     /// Returns iterator over all edges connecting start and end node.
     pub fn edges_connecting(
         &self,
@@ -295,11 +721,16 @@ where
         self.g.edge_endpoints(i)
     }
 
+    /// Returns the node for changes. The node is reindexed in the spatial index as it can be
+    /// moved.
     pub fn node_mut(&mut self, i: NodeIndex<Ix>) -> Option<&mut Node<N, E, Ty, Ix, Dn>> {
+        self.mark_node_dirty(i);
         self.g.node_weight_mut(i)
     }
 
+    /// Returns the edge for changes. The edge is reindexed in the spatial index.
     pub fn edge_mut(&mut self, i: EdgeIndex<Ix>) -> Option<&mut Edge<N, E, Ty, Ix, Dn, De>> {
+        self.mark_edge_dirty(i);
         self.g.edge_weight_mut(i)
     }
 
@@ -343,6 +774,107 @@ where
         self.dragged_node = node;
     }
 
+    pub fn hovered_node(&self) -> Option<NodeIndex<Ix>> {
+        self.hovered_node
+    }
+
+    pub fn set_hovered_node(&mut self, node: Option<NodeIndex<Ix>>) {
+        self.hovered_node = node;
+    }
+
+    pub fn hovered_edge(&self) -> Option<EdgeIndex<Ix>> {
+        self.hovered_edge
+    }
+
+    pub fn set_hovered_edge(&mut self, edge: Option<EdgeIndex<Ix>>) {
+        self.hovered_edge = edge;
+    }
+
+    /// Node with the keyboard focus, see [`crate::SettingsInteraction::with_keyboard_navigation_enabled`].
+    pub fn focused_node(&self) -> Option<NodeIndex<Ix>> {
+        self.focused_node
+    }
+
+    pub fn set_focused_node(&mut self, node: Option<NodeIndex<Ix>>) {
+        self.focused_node = node;
+    }
+
+    /// Nodes of the highlighted neighborhood, see [`crate::SettingsStyle::with_highlight_neighbors_enabled`].
+    pub fn highlighted_nodes(&self) -> &[NodeIndex<Ix>] {
+        &self.highlighted_nodes
+    }
+
+    pub fn set_highlighted_nodes(&mut self, nodes: Vec<NodeIndex<Ix>>) {
+        self.highlighted_nodes = nodes;
+    }
+
+    /// Edges of the highlighted neighborhood, see [`crate::SettingsStyle::with_highlight_neighbors_enabled`].
+    pub fn highlighted_edges(&self) -> &[EdgeIndex<Ix>] {
+        &self.highlighted_edges
+    }
+
+    pub fn set_highlighted_edges(&mut self, edges: Vec<EdgeIndex<Ix>>) {
+        self.highlighted_edges = edges;
+    }
+
+    /// Finds nodes reachable from the given nodes in at most `depth` hops following edges in the given
+    /// direction. Returns the found nodes, including the given ones, and the edges which lead to them.
+    pub fn neighborhood(
+        &self,
+        sources: &[NodeIndex<Ix>],
+        depth: usize,
+        direction: HighlightDirection,
+    ) -> (Vec<NodeIndex<Ix>>, Vec<EdgeIndex<Ix>>) {
+        let directions: &[Direction] = match direction {
+            HighlightDirection::Both => &[Direction::Outgoing, Direction::Incoming],
+            _ if !self.is_directed() => &[Direction::Outgoing],
+            HighlightDirection::Outgoing => &[Direction::Outgoing],
+            HighlightDirection::Incoming => &[Direction::Incoming],
+        };
+
+        let mut visited = HashSet::new();
+        let mut nodes = vec![];
+        let mut frontier = vec![];
+        for idx in sources {
+            if self.g.contains_node(*idx) && visited.insert(*idx) {
+                nodes.push(*idx);
+                frontier.push(*idx);
+            }
+        }
+
+        let mut traversed = HashSet::new();
+        let mut edges = vec![];
+        for _ in 0..depth {
+            let mut next = vec![];
+            for idx in frontier {
+                for dir in directions {
+                    for e in self.g.edges_directed(idx, *dir) {
+                        if traversed.insert(e.id()) {
+                            edges.push(e.id());
+                        }
+
+                        let other = if e.source() == idx {
+                            e.target()
+                        } else {
+                            e.source()
+                        };
+                        if visited.insert(other) {
+                            nodes.push(other);
+                            next.push(other);
+                        }
+                    }
+                }
+            }
+
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        (nodes, edges)
+    }
+
     pub fn edge_count(&self) -> usize {
         self.g.edge_count()
     }
@@ -351,3 +883,110 @@ where
         self.g.node_count()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighborhood() {
+        let mut graph: Graph<&str, ()> = Graph::new(StableGraph::default());
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        let d = graph.add_node("d");
+        let ab = graph.add_edge(a, b, ());
+        let bc = graph.add_edge(b, c, ());
+        let da = graph.add_edge(d, a, ());
+
+        let (nodes, edges) = graph.neighborhood(&[a], 1, HighlightDirection::Both);
+        assert_eq!((nodes, edges), (vec![a, b, d], vec![ab, da]));
+
+        let (nodes, edges) = graph.neighborhood(&[a], 2, HighlightDirection::Outgoing);
+        assert_eq!((nodes, edges), (vec![a, b, c], vec![ab, bc]));
+
+        let (nodes, edges) = graph.neighborhood(&[a], 0, HighlightDirection::Incoming);
+        assert_eq!((nodes, edges), (vec![a], vec![]));
+    }
+
+    #[test]
+    fn test_spatial_queries() {
+        let mut graph: Graph<&str, ()> = Graph::new(StableGraph::default());
+        let a = graph.add_node_with_location("a", Pos2::new(0., 0.));
+        let b = graph.add_node_with_location("b", Pos2::new(500., 0.));
+        let ab = graph.add_edge(a, b, ());
+
+        // linear scan before the index is built gives the same results
+        let near_a = Rect::from_center_size(Pos2::new(5., 5.), Vec2::splat(10.));
+        assert_eq!(graph.nodes_in_rect(near_a), vec![a]);
+        graph.update_spatial_index();
+        assert_eq!(graph.nodes_in_rect(near_a), vec![a]);
+        assert_eq!(graph.edges_in_rect(near_a), vec![ab]);
+        assert!(graph
+            .edges_in_rect(near_a.translate(Vec2::Y * 1000.))
+            .is_empty());
+
+        assert_eq!(graph.nearest_node(Pos2::new(400., 0.), 200.), Some(b));
+        assert_eq!(graph.nearest_node(Pos2::new(250., 300.), 200.), None);
+
+        graph
+            .node_mut(b)
+            .unwrap()
+            .set_location(Pos2::new(0., 1000.));
+        graph.update_spatial_index();
+        assert_eq!(graph.nearest_node(Pos2::new(400., 0.), 500.), Some(a));
+        assert!(graph
+            .edges_in_rect(near_a.translate(Vec2::Y * 1000.))
+            .contains(&ab));
+    }
+
+    #[test]
+    fn test_spatial_index_dirty() {
+        let mut graph: Graph<&str, ()> = Graph::new(StableGraph::default());
+        let a = graph.add_node_with_location("a", Pos2::new(0., 0.));
+        let b = graph.add_node_with_location("b", Pos2::new(500., 0.));
+        let ab = graph.add_edge(a, b, ());
+        graph.update_spatial_index();
+
+        let near = |x, y| Rect::from_center_size(Pos2::new(x, y), Vec2::splat(10.));
+        let far = near(0., 1000.);
+
+        // moved nodes are found before the index is updated
+        graph
+            .node_mut(b)
+            .unwrap()
+            .set_location(Pos2::new(0., 1000.));
+        assert_eq!(graph.nodes_in_rect(far), vec![b]);
+        assert!(graph.nodes_in_rect(near(500., 0.)).is_empty());
+        assert_eq!(graph.edges_in_rect(far), vec![ab]);
+        graph.update_spatial_index();
+        assert!(graph.spatial.dirty_nodes.is_empty() && graph.spatial.dirty_edges.is_empty());
+        assert_eq!(graph.nodes_in_rect(far), vec![b]);
+
+        // the index of the removed node is reused by the new one
+        graph.remove_node(b);
+        let c = graph.add_node_with_location("c", Pos2::new(500., 500.));
+        assert_eq!(c, b);
+        assert!(graph.nodes_in_rect(far).is_empty());
+        assert!(graph.edges_in_rect(far).is_empty());
+        assert_eq!(graph.nodes_in_rect(near(500., 500.)), vec![c]);
+        graph.update_spatial_index();
+        assert!(graph.nodes_in_rect(far).is_empty());
+        assert_eq!(graph.nodes_in_rect(near(500., 500.)), vec![c]);
+
+        // direct changes are picked up after marking the index
+        graph.g[c].set_location(Pos2::new(0., 1000.));
+        graph.mark_spatial_index_dirty();
+        assert_eq!(graph.nodes_in_rect(far), vec![c]);
+        graph.update_spatial_index();
+        assert_eq!(graph.nodes_in_rect(far), vec![c]);
+        assert!(!graph.spatial.stale);
+
+        // only the dirty elements are reindexed
+        graph.g[a].set_location(Pos2::new(0., 1000.));
+        graph.update_spatial_index();
+        assert_eq!(graph.nodes_in_rect(far), vec![c]);
+        graph.mark_moved_nodes();
+        assert_eq!(graph.nodes_in_rect(far), vec![a, c]);
+    }
+}
@@ -1,14 +1,24 @@
+use std::collections::HashSet;
 use std::marker::PhantomData;
 
 use crate::{
+    camera::{CameraAnimation, CameraTarget},
     draw::{DefaultEdgeShape, DefaultNodeShape, DrawContext, Drawer},
+    history::Change,
     layouts::{self, Layout, LayoutState},
-    metadata::Metadata,
+    metadata::{EdgeDraft, LabelEdit, LabelTarget, MenuTarget, Metadata},
+    minimap::Minimap,
+    node_size,
+    selection::{SelectionArea, SelectionMode, SelectionShape},
+    session::{Session, SessionCamera, SessionError},
     settings::{SettingsInteraction, SettingsNavigation, SettingsStyle},
-    DisplayEdge, DisplayNode, Graph,
+    Camera, DisplayEdge, DisplayNode, Edge, Graph, Highlight, Node, Subgraph,
 };
 
-use egui::{Id, PointerButton, Pos2, Rect, Response, Sense, Ui, Vec2, Widget};
+use egui::{
+    EventFilter, Id, Key, Modifiers, Painter, PointerButton, Pos2, Rect, Response, Sense, Shape,
+    Ui, Vec2, Widget,
+};
 
 use petgraph::{graph::EdgeIndex, stable_graph::DefaultIx};
 use petgraph::{graph::IndexType, Directed};
@@ -16,6 +26,48 @@ use petgraph::{stable_graph::NodeIndex, EdgeType};
 
 const KEY_LAYOUT: &str = "egui_grpahs_layout";
 
+/// Radius of the handles at the ends of the selected edges in the editing mode, in screen pixels.
+const EDGE_HANDLE_RADIUS: f32 = 5.;
+
+/// Part of the graph in screen pixels which stays visible when pan bounds are enabled.
+const PAN_BOUNDS_MARGIN: f32 = 50.;
+
+/// Scroll distance in screen pixels which zooms by the zoom speed.
+const SCROLL_ZOOM_STEP: f32 = 50.;
+
+/// Distance in screen pixels the graph is panned by a single arrow key press.
+const KEYBOARD_PAN_STEP: f32 = 50.;
+
+/// Renders tooltip contents for the hovered node.
+type NodeTooltip<'a, N, E, Ty, Ix, Nd> = Box<dyn FnMut(&mut Ui, &Node<N, E, Ty, Ix, Nd>) + 'a>;
+
+/// Renders tooltip contents for the hovered edge.
+type EdgeTooltip<'a, N, E, Ty, Ix, Nd, Ed> =
+    Box<dyn FnMut(&mut Ui, &Edge<N, E, Ty, Ix, Nd, Ed>) + 'a>;
+
+/// Offset of the pasted elements from the copied ones when the pointer position is unknown.
+const PASTE_OFFSET: f32 = 20.;
+
+/// Builds the context menu for the element under the pointer.
+type ContextMenu<'a, Ix> = Box<dyn FnMut(&mut Ui, ContextMenuTarget<Ix>) + 'a>;
+
+/// Element under the pointer when the context menu was opened with a secondary click.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContextMenuTarget<Ix: IndexType> {
+    Node(NodeIndex<Ix>),
+    Edge(EdgeIndex<Ix>),
+    /// Empty space of the canvas, position is in the canvas coordinates.
+    Canvas(Pos2),
+}
+
+/// Creates payload for the node created by the user at the canvas position.
+/// Returning `None` rejects the node.
+type NodeFactory<'a, N> = Box<dyn FnMut(Pos2) -> Option<N> + 'a>;
+
+/// Creates payload for the edge drawn by the user from the source to the target node.
+/// Returning `None` rejects the edge.
+type EdgeFactory<'a, E, Ix> = Box<dyn FnMut(NodeIndex<Ix>, NodeIndex<Ix>) -> Option<E> + 'a>;
+
 pub type DefaultGraphView<'a> = GraphView<
     'a,
     (),
@@ -30,9 +82,13 @@ pub type DefaultGraphView<'a> = GraphView<
 
 #[cfg(feature = "events")]
 use crate::events::{
-    Event, PayloadEdgeClick, PayloadEdgeDeselect, PayloadEdgeSelect, PayloadNodeClick,
-    PayloadNodeDeselect, PayloadNodeDoubleClick, PayloadNodeDragEnd, PayloadNodeDragStart,
-    PayloadNodeMove, PayloadNodeSelect, PayloadPan, PayloadZoom,
+    Event, PayloadCanvasSecondaryClick, PayloadEdgeClick, PayloadEdgeCreate,
+    PayloadEdgeCreateRequest, PayloadEdgeDeselect, PayloadEdgeHoverEnter, PayloadEdgeHoverLeave,
+    PayloadEdgeLabelChange, PayloadEdgeReconnect, PayloadEdgeRemove, PayloadEdgeSecondaryClick,
+    PayloadEdgeSelect, PayloadNodeClick, PayloadNodeCreate, PayloadNodeDeselect,
+    PayloadNodeDoubleClick, PayloadNodeDragEnd, PayloadNodeDragStart, PayloadNodeHoverEnter,
+    PayloadNodeHoverLeave, PayloadNodeLabelChange, PayloadNodeMove, PayloadNodeRemove,
+    PayloadNodeSecondaryClick, PayloadNodeSelect, PayloadPan, PayloadZoom,
 };
 #[cfg(feature = "events")]
 use crossbeam::channel::Sender;
@@ -78,6 +134,14 @@ pub struct GraphView<
     settings_navigation: SettingsNavigation,
     settings_style: SettingsStyle,
 
+    node_factory: Option<NodeFactory<'a, N>>,
+    edge_factory: Option<EdgeFactory<'a, E, Ix>>,
+    clipboard: Option<&'a mut Option<Subgraph<N, E>>>,
+    node_tooltip: Option<NodeTooltip<'a, N, E, Ty, Ix, Nd>>,
+    edge_tooltip: Option<EdgeTooltip<'a, N, E, Ty, Ix, Nd, Ed>>,
+    context_menu: Option<ContextMenu<'a, Ix>>,
+    id: Option<Id>,
+
     #[cfg(feature = "events")]
     events_publisher: Option<&'a Sender<Event>>,
 
@@ -96,16 +160,36 @@ where
     L: Layout<S>,
 {
     fn ui(self, ui: &mut Ui) -> Response {
-        self.sync_layout(ui);
+        let layout_settled = self.sync_layout(ui);
 
         let mut meta = Metadata::load(ui);
         self.sync_state(&mut meta);
-
-        let (resp, p) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        self.g.history.begin_group();
+
+        let (resp, p) = match self.id {
+            Some(id) => {
+                let (rect, _) = ui.allocate_exact_size(ui.available_size(), Sense::hover());
+                (
+                    ui.interact(rect, id, Sense::click_and_drag()),
+                    ui.painter_at(rect),
+                )
+            }
+            None => ui.allocate_painter(ui.available_size(), Sense::click_and_drag()),
+        };
         self.handle_fit_to_screen(&resp, &mut meta);
+        self.handle_area_selection(ui, &resp, &mut meta);
+        self.handle_edge_draft(ui, &resp, &mut meta);
         self.handle_navigation(ui, &resp, &mut meta);
+        self.handle_camera(ui, &resp, &mut meta);
+        self.handle_pan_bounds(&resp, &mut meta);
         self.handle_node_drag(&resp, &mut meta);
         self.handle_click(&resp, &mut meta);
+        self.handle_keyboard(ui, &resp, &mut meta);
+        self.handle_label_editing(ui, &resp, &mut meta);
+        self.handle_hover(&resp, &meta);
+        self.handle_highlight();
+        self.handle_editing(ui, &resp, &meta);
+        self.handle_clipboard(ui, &resp, &meta);
 
         Drawer::<N, E, Ty, Ix, Nd, Ed, S, L>::new(
             self.g,
@@ -118,12 +202,23 @@ where
             },
         )
         .draw();
-
+        GraphView::<N, E, Ty, Ix, Nd, Ed, S, L>::draw_selection_area(ui, &p, &meta);
+        self.draw_edge_handles(ui, &p, &meta);
+        self.draw_edge_draft(ui, &p, &meta);
+        self.show_label_editor(ui, &mut meta);
+        self.handle_minimap(ui, &resp, &p, &mut meta);
+        self.show_tooltip(&resp);
+        self.handle_context_menu(&resp, &mut meta);
+        #[cfg(feature = "accesskit")]
+        self.update_accesskit(ui, &resp, &meta);
+
+        self.g.history.end_group();
+        self.handle_undo_redo(ui, &resp);
+
+        self.handle_repaint(ui, &meta, layout_settled);
         meta.first_frame = false;
         meta.save(ui);
 
-        ui.ctx().request_repaint();
-
         resp
     }
 }
@@ -149,6 +244,14 @@ where
             settings_interaction: SettingsInteraction::default(),
             settings_navigation: SettingsNavigation::default(),
 
+            node_factory: Option::default(),
+            edge_factory: Option::default(),
+            clipboard: Option::default(),
+            node_tooltip: Option::default(),
+            edge_tooltip: Option::default(),
+            context_menu: Option::default(),
+            id: Option::default(),
+
             #[cfg(feature = "events")]
             events_publisher: Option::default(),
 
@@ -156,6 +259,13 @@ where
         }
     }
 
+    /// Sets the id of the widget, e.g. to control its camera with [`Camera`]. Without it the id
+    /// is generated by egui and can be taken from the response of the widget.
+    pub fn with_id(mut self, id: Id) -> Self {
+        self.id = Some(id);
+        self
+    }
+
     /// Makes widget interactive according to the provided settings.
     pub fn with_interactions(mut self, settings_interaction: &SettingsInteraction) -> Self {
         self.settings_interaction = settings_interaction.clone();
@@ -174,6 +284,64 @@ where
         self
     }
 
+    /// Sets the function which creates payloads for the nodes created by the user in the editing
+    /// mode. The function gets the canvas position of the node and can reject it by returning `None`.
+    pub fn with_node_factory(mut self, factory: impl FnMut(Pos2) -> Option<N> + 'a) -> Self {
+        self.node_factory = Some(Box::new(factory));
+        self
+    }
+
+    /// Sets the function which creates payloads for the edges drawn by the user. The function gets
+    /// the source and the target nodes and can reject the edge by returning `None`.
+    ///
+    /// Without the factory drawn edges are only reported with the `EdgeCreateRequest` event and
+    /// the application decides whether to add them.
+    pub fn with_edge_factory(
+        mut self,
+        factory: impl FnMut(NodeIndex<Ix>, NodeIndex<Ix>) -> Option<E> + 'a,
+    ) -> Self {
+        self.edge_factory = Some(Box::new(factory));
+        self
+    }
+
+    /// Enables copy, cut and paste of the selected nodes and the edges between them with
+    /// `Ctrl+C`, `Ctrl+X` and `Ctrl+V` (`Cmd` on Mac) while the pointer is over the graph.
+    ///
+    /// The clipboard is owned by the application, so it can be shared between several graphs.
+    /// Pasted elements are centered at the pointer and become the selection.
+    pub fn with_clipboard(mut self, clipboard: &'a mut Option<Subgraph<N, E>>) -> Self {
+        self.clipboard = Some(clipboard);
+        self
+    }
+
+    /// Sets the function which renders the tooltip shown at the pointer while a node is hovered.
+    pub fn with_node_tooltip(
+        mut self,
+        tooltip: impl FnMut(&mut Ui, &Node<N, E, Ty, Ix, Dn>) + 'a,
+    ) -> Self {
+        self.node_tooltip = Some(Box::new(tooltip));
+        self
+    }
+
+    /// Sets the function which renders the tooltip shown at the pointer while an edge is hovered.
+    pub fn with_edge_tooltip(
+        mut self,
+        tooltip: impl FnMut(&mut Ui, &Edge<N, E, Ty, Ix, Dn, De>) + 'a,
+    ) -> Self {
+        self.edge_tooltip = Some(Box::new(tooltip));
+        self
+    }
+
+    /// Sets the function which builds the context menu opened with a secondary click on a node,
+    /// an edge or the empty space of the canvas.
+    pub fn with_context_menu(
+        mut self,
+        context_menu: impl FnMut(&mut Ui, ContextMenuTarget<Ix>) + 'a,
+    ) -> Self {
+        self.context_menu = Some(Box::new(context_menu));
+        self
+    }
+
     /// Clears cached values of layout and metadata.
     pub fn clear_cache(ui: &mut Ui) {
         GraphView::<N, E, Ty, Ix, Dn, De, S, L>::reset_metadata(ui);
@@ -192,6 +360,44 @@ where
         });
     }
 
+    /// Captures the graph together with the camera and the layout state stored in egui memory.
+    pub fn save_session(ui: &Ui, g: &Graph<N, E, Ty, Ix, Dn, De>) -> Session<N, E, S> {
+        let camera = SessionCamera::from_metadata(&Metadata::load(ui));
+        let layout = ui
+            .data_mut(|data| data.get_persisted::<S>(Id::new(KEY_LAYOUT)))
+            .unwrap_or_default();
+
+        Session::from_graph(g)
+            .with_camera(camera)
+            .with_layout(layout)
+    }
+
+    /// Restores the graph from the session and puts its camera and layout state to egui memory.
+    /// Camera and layout state missing in the session are reset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SessionError`] if the graph could not be restored. Egui memory is not changed
+    /// in this case.
+    pub fn load_session(
+        ui: &mut Ui,
+        session: Session<N, E, S>,
+    ) -> Result<Graph<N, E, Ty, Ix, Dn, De>, SessionError> {
+        let camera = session.camera.clone();
+        let layout = session.layout.clone();
+        let g = session.into_graph()?;
+
+        match camera {
+            Some(camera) => camera.to_metadata().save(ui),
+            None => GraphView::<N, E, Ty, Ix, Dn, De, S, L>::reset_metadata(ui),
+        }
+        ui.data_mut(|data| {
+            data.insert_persisted(Id::new(KEY_LAYOUT), layout.unwrap_or_default());
+        });
+
+        Ok(g)
+    }
+
     #[cfg(feature = "events")]
     /// Allows to supply channel where events happening in the graph will be reported.
     pub fn with_events(mut self, events_publisher: &'a Sender<Event>) -> Self {
@@ -199,7 +405,8 @@ where
         self
     }
 
-    fn sync_layout(&mut self, ui: &mut Ui) {
+    /// Runs the layout step and returns whether the layout is settled.
+    fn sync_layout(&mut self, ui: &mut Ui) -> bool {
         ui.data_mut(|data| {
             let state = data
                 .get_persisted::<S>(Id::new(KEY_LAYOUT))
@@ -207,20 +414,43 @@ where
             let mut layout = L::from_state(state);
             layout.next(self.g);
 
-            data.insert_persisted(Id::new(KEY_LAYOUT), layout.state());
-        });
+            let state = layout.state();
+            let settled = state.is_settled();
+            data.insert_persisted(Id::new(KEY_LAYOUT), state);
+            settled
+        })
+    }
+
+    /// Requests the next frame only while the graph changes without the user input: the layout
+    /// is not settled, the camera is animated or the node is dragged. The first frame is repeated
+    /// as node sizes are known only after the nodes are drawn. egui repaints on input by itself.
+    fn handle_repaint(&self, ui: &Ui, meta: &Metadata, layout_settled: bool) {
+        let animating = meta.first_frame || meta.camera_animation.is_some();
+        let dragging = self.g.dragged_node().is_some();
+        if !layout_settled || animating || dragging {
+            ui.ctx().request_repaint();
+        }
     }
 
     fn sync_state(&mut self, meta: &mut Metadata) {
         let mut selected_nodes = Vec::new();
         let mut selected_edges = Vec::new();
         let mut dragged = None;
+        let mut hovered_node = None;
+        let mut hovered_edge = None;
+        let mut focused = None;
 
         meta.reset_bounds();
         self.g.nodes_iter().for_each(|(idx, n)| {
             if n.dragged() {
                 dragged = Some(idx);
             }
+            if n.hovered() {
+                hovered_node = Some(idx);
+            }
+            if n.focused() {
+                focused = Some(idx);
+            }
             if n.selected() {
                 selected_nodes.push(idx);
             }
@@ -232,11 +462,19 @@ where
             if e.selected() {
                 selected_edges.push(idx);
             }
+            if e.hovered() {
+                hovered_edge = Some(idx);
+            }
         });
 
         self.g.set_selected_nodes(selected_nodes);
         self.g.set_selected_edges(selected_edges);
         self.g.set_dragged_node(dragged);
+        self.g.set_hovered_node(hovered_node);
+        self.g.set_hovered_edge(hovered_edge);
+        self.g.set_focused_node(focused);
+        self.g.mark_moved_nodes();
+        self.g.update_spatial_index();
     }
 
     /// Fits the graph to the screen if it is the first frame or
@@ -369,202 +607,1446 @@ where
         self.select_edge(idx);
     }
 
-    fn handle_node_drag(&mut self, resp: &Response, meta: &mut Metadata) {
-        if !self.settings_interaction.dragging_enabled {
+    fn handle_area_selection(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_interaction.box_selection_enabled
+            && !self.settings_interaction.lasso_selection_enabled
+        {
             return;
         }
 
-        if !resp.dragged_by(PointerButton::Primary)
-            && !resp.drag_started_by(PointerButton::Primary)
-            && !resp.drag_stopped_by(PointerButton::Primary)
-        {
+        if resp.drag_started_by(PointerButton::Primary) {
+            let (origin, modifiers) = ui.input(|i| (i.pointer.press_origin(), i.modifiers));
+            let settings = &self.settings_interaction;
+            let is_box = settings.box_selection_enabled
+                && modifiers.contains(settings.box_selection_modifier);
+            let is_lasso = !is_box
+                && settings.lasso_selection_enabled
+                && modifiers.contains(settings.lasso_selection_modifier);
+            if let Some(origin) = origin.filter(|_| is_box || is_lasso) {
+                let tool = if is_box {
+                    settings.box_selection_modifier
+                } else {
+                    settings.lasso_selection_modifier
+                };
+                // add and remove modifiers are held together with the tool modifier,
+                // so the modifiers are checked to be held and not to match exactly
+                let held = |pattern: Modifiers| {
+                    !pattern.is_none() && !tool.contains(pattern) && modifiers.contains(pattern)
+                };
+                if self.g.node_by_screen_pos(meta, origin).is_none() {
+                    let mode = if held(settings.box_selection_remove_modifier) {
+                        SelectionMode::Remove
+                    } else if held(settings.box_selection_add_modifier) {
+                        SelectionMode::Add
+                    } else {
+                        SelectionMode::Replace
+                    };
+                    let start = meta.screen_to_canvas_pos(origin);
+                    meta.selection = Some(if is_lasso {
+                        SelectionArea::new_lasso(mode, start)
+                    } else {
+                        SelectionArea::new_rect(mode, start)
+                    });
+                }
+            }
+        }
+
+        if meta.selection.is_none() {
             return;
         }
 
-        if resp.drag_started() {
-            if let Some(idx) = self.g.node_by_screen_pos(meta, resp.hover_pos().unwrap()) {
-                self.set_drag_start(idx);
+        if let Some(pos) = ui.input(|i| i.pointer.interact_pos()) {
+            let end = meta.screen_to_canvas_pos(pos);
+            if let Some(area) = meta.selection.as_mut() {
+                area.extend(end);
             }
         }
 
-        // handle mouse drag
-        if resp.dragged()
-            && self.g.dragged_node().is_some()
-            && (resp.drag_delta().x.abs() > 0. || resp.drag_delta().y.abs() > 0.)
-        {
-            let n_idx_dragged = self.g.dragged_node().unwrap();
-            let delta_in_graph_coords = resp.drag_delta() / meta.zoom;
-            self.move_node(n_idx_dragged, delta_in_graph_coords);
+        if !resp.dragged() {
+            if let Some(area) = meta.selection.take() {
+                self.apply_selection_area(&area);
+            }
         }
+    }
 
-        // compensate movement of the node which is not caused by dragging
-        if let Some(n_idx_dragged) = self.g.dragged_node() {
-            if let Some(mouse_pos) = resp.hover_pos() {
-                if let Some(node) = self.g.node(n_idx_dragged) {
-                    let node_pos = node.location() * meta.zoom + meta.pan;
-                    let delta = mouse_pos - node_pos;
+    fn handle_edge_draft(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        let settings = &self.settings_interaction;
+        if !settings.edge_creation_enabled && !settings.editing_enabled {
+            return;
+        }
 
-                    self.move_node(n_idx_dragged, delta / meta.zoom);
+        if resp.drag_started_by(PointerButton::Primary) {
+            let (origin, modifiers) = ui.input(|i| (i.pointer.press_origin(), i.modifiers));
+            if let Some(origin) = origin {
+                meta.edge_draft = self.edge_handle_by_screen_pos(meta, origin);
+                if meta.edge_draft.is_none()
+                    && settings.edge_creation_enabled
+                    && modifiers.contains(settings.edge_creation_modifier)
+                {
+                    if let Some(source) = self.g.node_by_screen_pos(meta, origin) {
+                        meta.edge_draft = Some(EdgeDraft {
+                            anchor: source.index(),
+                            end: meta.screen_to_canvas_pos(origin),
+                            reconnect: None,
+                        });
+                    }
                 }
             }
         }
 
-        if resp.drag_stopped() && self.g.dragged_node().is_some() {
-            let n_idx = self.g.dragged_node().unwrap();
-            self.set_drag_end(n_idx);
+        if meta.edge_draft.is_none() {
+            return;
         }
-    }
 
-    fn fit_to_screen(&self, rect: &Rect, meta: &mut Metadata) {
-        // calculate graph dimensions with decorative padding
-        let bounds = meta.graph_bounds();
-        let mut diag = bounds.max - bounds.min;
+        if let Some(pos) = ui.input(|i| i.pointer.interact_pos()) {
+            let end = meta.screen_to_canvas_pos(pos);
+            if let Some(draft) = meta.edge_draft.as_mut() {
+                draft.end = end;
+            }
+        }
 
-        // if the graph is empty or consists from one node, use a default size
-        if diag == Vec2::ZERO {
-            diag = Vec2::new(1., 100.);
+        if resp.dragged() {
+            return;
         }
 
-        let graph_size = diag * (1. + self.settings_navigation.screen_padding);
-        let (width, height) = (graph_size.x, graph_size.y);
+        let draft = meta.edge_draft.take().unwrap();
+        let end = meta.canvas_to_screen_pos(draft.end);
+        let Some(target) = self.g.node_by_screen_pos(meta, end) else {
+            return;
+        };
+        let anchor = NodeIndex::new(draft.anchor);
+        match draft.reconnect {
+            Some((edge, true)) => self.reconnect_edge(EdgeIndex::new(edge), target, anchor),
+            Some((edge, false)) => self.reconnect_edge(EdgeIndex::new(edge), anchor, target),
+            None => self.request_edge(anchor, target),
+        }
+    }
 
-        // calculate canvas dimensions
-        let canvas_size = rect.size();
-        let (canvas_width, canvas_height) = (canvas_size.x, canvas_size.y);
+    /// Finds the handle of a selected edge under the pointer and starts reconnecting the edge.
+    fn edge_handle_by_screen_pos(&self, meta: &Metadata, screen_pos: Pos2) -> Option<EdgeDraft> {
+        if !self.settings_interaction.editing_enabled {
+            return None;
+        }
 
-        // calculate zoom factors for x and y to fit the graph inside the canvas
-        let zoom_x = canvas_width / width;
-        let zoom_y = canvas_height / height;
+        let pos = meta.screen_to_canvas_pos(screen_pos);
+        let radius = EDGE_HANDLE_RADIUS / meta.zoom;
+        self.g.selected_edges().iter().find_map(|idx| {
+            let (start, end) = self.g.edge_endpoints(*idx)?;
+            let (start_handle, end_handle) = self.edge_handles(*idx)?;
+            let (anchor, start_moved) = if start_handle.distance(pos) <= radius {
+                (end, true)
+            } else if end_handle.distance(pos) <= radius {
+                (start, false)
+            } else {
+                return None;
+            };
+
+            Some(EdgeDraft {
+                anchor: anchor.index(),
+                end: pos,
+                reconnect: Some((idx.index(), start_moved)),
+            })
+        })
+    }
 
-        // choose the minimum of the two zoom factors to avoid distortion
-        let new_zoom = zoom_x.min(zoom_y);
+    /// Returns canvas positions of the handles at the start and the end of the edge.
+    /// Self loops have no handles.
+    fn edge_handles(&self, idx: EdgeIndex<Ix>) -> Option<(Pos2, Pos2)> {
+        let (start, end) = self.g.edge_endpoints(idx)?;
+        if start == end {
+            return None;
+        }
 
-        // calculate the zoom delta and call handle_zoom to adjust the zoom factor
-        let zoom_delta = new_zoom / meta.zoom - 1.0;
-        self.zoom(rect, zoom_delta, None, meta);
+        let (start, end) = (self.g.node(start)?, self.g.node(end)?);
+        let dir = (end.location() - start.location()).normalized();
+        Some((
+            start.display().closest_boundary_point(dir),
+            end.display().closest_boundary_point(-dir),
+        ))
+    }
 
-        // calculate the center of the graph and the canvas
-        let graph_center = (bounds.min.to_vec2() + bounds.max.to_vec2()) / 2.0;
+    fn edge_allowed(&self, source: NodeIndex<Ix>, target: NodeIndex<Ix>) -> bool {
+        if source == target && !self.settings_interaction.edge_creation_self_loops_enabled {
+            return false;
+        }
 
-        // adjust the pan value to align the centers of the graph and the canvas
-        let new_pan = rect.center().to_vec2() - graph_center * new_zoom;
-        self.set_pan(new_pan, meta);
+        self.settings_interaction.edge_creation_duplicates_enabled
+            || self.g.g.find_edge(source, target).is_none()
     }
 
-    fn handle_navigation(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
-        if !meta.first_frame {
-            meta.pan += resp.rect.left_top() - meta.top_left;
+    fn request_edge(&mut self, source: NodeIndex<Ix>, target: NodeIndex<Ix>) {
+        if !self.settings_interaction.edge_creation_enabled || !self.edge_allowed(source, target) {
+            return;
         }
-        meta.top_left = resp.rect.left_top();
 
-        self.handle_zoom(ui, resp, meta);
-        self.handle_pan(resp, meta);
+        #[cfg(feature = "events")]
+        self.publish_event(Event::EdgeCreateRequest(PayloadEdgeCreateRequest {
+            source: source.index(),
+            target: target.index(),
+        }));
+
+        let Some(factory) = self.edge_factory.as_mut() else {
+            return;
+        };
+        if let Some(payload) = factory(source, target) {
+            let idx = self.g.add_edge(source, target, payload);
+
+            #[cfg(feature = "events")]
+            self.publish_event(Event::EdgeCreate(PayloadEdgeCreate {
+                id: idx.index(),
+                source: source.index(),
+                target: target.index(),
+            }));
+            #[cfg(not(feature = "events"))]
+            let _ = idx;
+        }
     }
 
-    fn handle_zoom(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
-        if !self.settings_navigation.zoom_and_pan_enabled {
+    fn reconnect_edge(&mut self, idx: EdgeIndex<Ix>, source: NodeIndex<Ix>, target: NodeIndex<Ix>) {
+        if self.g.edge_endpoints(idx) == Some((source, target))
+            || !self.edge_allowed(source, target)
+        {
             return;
         }
 
-        ui.input(|i| {
-            let delta = i.zoom_delta();
-            if delta == 1. {
-                return;
-            }
+        let Some(new_idx) = self.g.reconnect_edge(idx, source, target) else {
+            return;
+        };
 
-            let step = self.settings_navigation.zoom_speed * (delta - 1.).signum();
-            self.zoom(&resp.rect, step, i.pointer.hover_pos(), meta);
-        });
+        #[cfg(feature = "events")]
+        self.publish_event(Event::EdgeReconnect(PayloadEdgeReconnect {
+            id: new_idx.index(),
+            source: source.index(),
+            target: target.index(),
+        }));
+        #[cfg(not(feature = "events"))]
+        let _ = new_idx;
     }
 
-    fn handle_pan(&self, resp: &Response, meta: &mut Metadata) {
-        if !self.settings_navigation.zoom_and_pan_enabled {
+    fn handle_editing(&mut self, ui: &Ui, resp: &Response, meta: &Metadata) {
+        if !self.settings_interaction.editing_enabled {
             return;
         }
 
-        if (resp.dragged_by(PointerButton::Middle) || resp.dragged_by(PointerButton::Primary))
-            && self.g.dragged_node().is_none()
-            && (resp.drag_delta().x.abs() > 0. || resp.drag_delta().y.abs() > 0.)
-        {
-            let new_pan = meta.pan + resp.drag_delta();
-            self.set_pan(new_pan, meta);
+        if resp.double_clicked() {
+            if let Some(pos) = resp.hover_pos() {
+                if self.g.node_by_screen_pos(meta, pos).is_none()
+                    && self.g.edge_by_screen_pos(meta, pos).is_none()
+                {
+                    self.create_node(meta.screen_to_canvas_pos(pos));
+                }
+            }
+        }
+
+        // keys go to the graph when it has the keyboard focus, e.g. from the keyboard navigation,
+        // or when it is hovered and no other widget has the focus
+        let has_keys = match ui.memory(egui::Memory::focused) {
+            Some(focused) => focused == resp.id,
+            None => resp.hovered(),
+        };
+        let delete_pressed =
+            ui.input(|i| i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace));
+        if delete_pressed && has_keys {
+            self.remove_selected();
         }
     }
 
-    /// Zooms the graph by the given delta. It also compensates with pan to keep the zoom center in the same place.
-    fn zoom(&self, rect: &Rect, delta: f32, zoom_center: Option<Pos2>, meta: &mut Metadata) {
-        let center_pos = zoom_center.unwrap_or(rect.center()).to_vec2();
-        let graph_center_pos = (center_pos - meta.pan) / meta.zoom;
-        let factor = 1. + delta;
-        let new_zoom = meta.zoom * factor;
+    fn handle_clipboard(&mut self, ui: &Ui, resp: &Response, meta: &Metadata) {
+        if self.clipboard.is_none() || !resp.hovered() || ui.ctx().wants_keyboard_input() {
+            return;
+        }
 
-        let pan_delta = graph_center_pos * meta.zoom - graph_center_pos * new_zoom;
-        let new_pan = meta.pan + pan_delta;
+        // integrations report the shortcuts either as clipboard events or as plain key presses
+        let (copy, cut, paste) = ui.input_mut(|i| {
+            let has_event = |expected: &egui::Event| i.events.iter().any(|e| e == expected);
+            let copy = has_event(&egui::Event::Copy);
+            let cut = has_event(&egui::Event::Cut);
+            let paste = i.events.iter().any(|e| matches!(e, egui::Event::Paste(_)));
+            (
+                i.consume_key(Modifiers::COMMAND, Key::C) || copy,
+                i.consume_key(Modifiers::COMMAND, Key::X) || cut,
+                i.consume_key(Modifiers::COMMAND, Key::V) || paste,
+            )
+        });
 
-        self.set_pan(new_pan, meta);
-        self.set_zoom(new_zoom, meta);
-    }
+        if copy || cut {
+            if let Some(subgraph) = Subgraph::from_selection(self.g) {
+                if let Some(clipboard) = self.clipboard.as_deref_mut() {
+                    *clipboard = Some(subgraph);
+                }
+                if cut {
+                    self.remove_selected();
+                }
+            }
+        }
 
-    fn select_node(&mut self, idx: NodeIndex<Ix>) {
-        let n = self.g.node_mut(idx).unwrap();
-        n.set_selected(true);
+        if paste {
+            let Some(subgraph) = self.clipboard.as_deref().and_then(Option::as_ref) else {
+                return;
+            };
+            let center = resp
+                .hover_pos()
+                .map_or(subgraph.origin + Vec2::splat(PASTE_OFFSET), |pos| {
+                    meta.screen_to_canvas_pos(pos)
+                });
+            let (nodes, edges) = subgraph.paste_into(self.g, center);
 
-        #[cfg(feature = "events")]
-        self.publish_event(Event::NodeSelect(PayloadNodeSelect { id: idx.index() }));
+            self.deselect_all();
+            for idx in nodes {
+                self.select_node(idx);
+            }
+            for idx in edges {
+                self.select_edge(idx);
+            }
+        }
     }
 
-    fn deselect_node(&mut self, idx: NodeIndex<Ix>) {
-        let n = self.g.node_mut(idx).unwrap();
-        n.set_selected(false);
+    fn handle_label_editing(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_interaction.label_editing_enabled || meta.label_edit.is_some() {
+            return;
+        }
 
-        #[cfg(feature = "events")]
-        self.publish_event(Event::NodeDeselect(PayloadNodeDeselect { id: idx.index() }));
-    }
+        let target = if resp.double_clicked() {
+            resp.hover_pos().and_then(|pos| {
+                if let Some(idx) = self.g.node_by_screen_pos(meta, pos) {
+                    return Some(LabelTarget::Node(idx.index()));
+                }
+                self.g
+                    .edge_by_screen_pos(meta, pos)
+                    .map(|idx| LabelTarget::Edge(idx.index()))
+            })
+        } else if resp.hovered()
+            && !ui.ctx().wants_keyboard_input()
+            && ui.input(|i| i.key_pressed(Key::F2))
+        {
+            let node = self.g.selected_nodes().first();
+            let edge = self.g.selected_edges().first();
+            node.map(|idx| LabelTarget::Node(idx.index()))
+                .or_else(|| edge.map(|idx| LabelTarget::Edge(idx.index())))
+        } else {
+            None
+        };
 
-    #[allow(unused_variables, clippy::unused_self)]
-    fn set_node_clicked(&self, idx: NodeIndex<Ix>) {
-        #[cfg(feature = "events")]
-        self.publish_event(Event::NodeClick(PayloadNodeClick { id: idx.index() }));
+        let Some(target) = target else {
+            return;
+        };
+        let text = match target {
+            LabelTarget::Node(idx) => self.g.node(NodeIndex::new(idx)).map(Node::label),
+            LabelTarget::Edge(idx) => self.g.edge(EdgeIndex::new(idx)).map(Edge::label),
+        };
+        if let Some(text) = text {
+            meta.label_edit = Some(LabelEdit {
+                target,
+                text,
+                focused: false,
+            });
+        }
     }
 
-    #[allow(unused_variables, clippy::unused_self)]
-    fn set_node_double_clicked(&self, idx: NodeIndex<Ix>) {
-        #[cfg(feature = "events")]
-        self.publish_event(Event::NodeDoubleClick(PayloadNodeDoubleClick {
-            id: idx.index(),
-        }));
-    }
+    /// Shows the text field of the edited label and applies or cancels the edit.
+    fn show_label_editor(&mut self, ui: &Ui, meta: &mut Metadata) {
+        let Some(mut edit) = meta.label_edit.take() else {
+            return;
+        };
+        let Some(anchor) = self.label_anchor(edit.target) else {
+            return;
+        };
 
-    #[allow(unused_variables, clippy::unused_self)]
-    fn set_edge_clicked(&self, idx: EdgeIndex<Ix>) {
-        #[cfg(feature = "events")]
-        self.publish_event(Event::EdgeClick(PayloadEdgeClick { id: idx.index() }));
-    }
+        let pos = meta.canvas_to_screen_pos(anchor);
+        let resp = egui::Area::new(ui.id().with("egui_graphs_label_edit"))
+            .fixed_pos(pos)
+            .pivot(egui::Align2::CENTER_BOTTOM)
+            .order(egui::Order::Foreground)
+            .show(ui.ctx(), |ui| {
+                ui.add(egui::TextEdit::singleline(&mut edit.text).desired_width(120.))
+            })
+            .inner;
+
+        if !edit.focused {
+            resp.request_focus();
+            edit.focused = true;
+            meta.label_edit = Some(edit);
+            return;
+        }
 
-    fn select_edge(&mut self, idx: EdgeIndex<Ix>) {
-        let e = self.g.edge_mut(idx).unwrap();
-        e.set_selected(true);
+        if !resp.lost_focus() {
+            meta.label_edit = Some(edit);
+            return;
+        }
 
-        #[cfg(feature = "events")]
-        self.publish_event(Event::EdgeSelect(PayloadEdgeSelect { id: idx.index() }));
+        if ui.input(|i| i.key_pressed(Key::Escape)) {
+            return;
+        }
+        self.set_label(edit.target, &edit.text);
     }
 
-    fn deselect_edge(&mut self, idx: EdgeIndex<Ix>) {
-        let e = self.g.edge_mut(idx).unwrap();
-        e.set_selected(false);
-
-        #[cfg(feature = "events")]
-        self.publish_event(Event::EdgeDeselect(PayloadEdgeDeselect { id: idx.index() }));
+    /// Returns the canvas position above which the text field of the label is shown.
+    fn label_anchor(&self, target: LabelTarget) -> Option<Pos2> {
+        match target {
+            LabelTarget::Node(idx) => {
+                let n = self.g.node(NodeIndex::new(idx))?;
+                Some(n.display().closest_boundary_point(Vec2::new(0., -1.)))
+            }
+            LabelTarget::Edge(idx) => {
+                let (start, end) = self.g.edge_endpoints(EdgeIndex::new(idx))?;
+                let (start, end) = (self.g.node(start)?.location(), self.g.node(end)?.location());
+                Some(start + (end - start) / 2.)
+            }
+        }
     }
 
-    /// Deselects all nodes AND edges.
-    fn deselect_all(&mut self) {
-        self.deselect_all_nodes();
-        self.deselect_all_edges();
-    }
+    fn set_label(&mut self, target: LabelTarget, label: &str) {
+        match target {
+            LabelTarget::Node(idx) => {
+                let idx = NodeIndex::new(idx);
+                let Some(n) = self.g.node(idx) else {
+                    return;
+                };
+                if n.label() == label {
+                    return;
+                }
+                self.g.set_node_label(idx, label.to_string());
 
-    fn deselect_all_nodes(&mut self) {
-        let selected_nodes = self.g.selected_nodes().to_vec();
+                #[cfg(feature = "events")]
+                self.publish_event(Event::NodeLabelChange(PayloadNodeLabelChange {
+                    id: idx.index(),
+                    label: label.to_string(),
+                }));
+            }
+            LabelTarget::Edge(idx) => {
+                let idx = EdgeIndex::new(idx);
+                let Some(e) = self.g.edge(idx) else {
+                    return;
+                };
+                if e.label() == label {
+                    return;
+                }
+                self.g.set_edge_label(idx, label.to_string());
+
+                #[cfg(feature = "events")]
+                self.publish_event(Event::EdgeLabelChange(PayloadEdgeLabelChange {
+                    id: idx.index(),
+                    label: label.to_string(),
+                }));
+            }
+        }
+    }
+
+    /// Moves the node focus, selects the focused node, zooms and pans with the keyboard.
+    fn handle_keyboard(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_interaction.keyboard_navigation_enabled {
+            return;
+        }
+
+        if resp.clicked() {
+            resp.request_focus();
+        }
+        if !resp.has_focus() {
+            return;
+        }
+
+        // keep tab and arrows inside of the graph instead of moving the focus to other widgets
+        ui.memory_mut(|m| {
+            m.set_focus_lock_filter(
+                resp.id,
+                EventFilter {
+                    tab: true,
+                    horizontal_arrows: true,
+                    vertical_arrows: true,
+                    escape: false,
+                },
+            );
+        });
+
+        let arrows = [
+            (Key::ArrowLeft, Vec2::new(-1., 0.)),
+            (Key::ArrowRight, Vec2::new(1., 0.)),
+            (Key::ArrowUp, Vec2::new(0., -1.)),
+            (Key::ArrowDown, Vec2::new(0., 1.)),
+        ];
+        let (pan, dir, tab, select, zoom) = ui.input_mut(|i| {
+            // modifier variants go first because consuming ignores extra shift
+            let pan = arrows
+                .iter()
+                .filter(|(key, _)| i.consume_key(Modifiers::COMMAND, *key))
+                .fold(Vec2::ZERO, |acc, (_, dir)| acc - *dir);
+            let dir = arrows
+                .iter()
+                .find(|(key, _)| i.consume_key(Modifiers::NONE, *key))
+                .map(|(_, dir)| *dir);
+            let tab = if i.consume_key(Modifiers::SHIFT, Key::Tab) {
+                Some(false)
+            } else if i.consume_key(Modifiers::NONE, Key::Tab) {
+                Some(true)
+            } else {
+                None
+            };
+            let select = i.consume_key(Modifiers::NONE, Key::Enter);
+            let zoom = if i.consume_key(Modifiers::NONE, Key::Plus)
+                || i.consume_key(Modifiers::NONE, Key::Equals)
+            {
+                1.
+            } else if i.consume_key(Modifiers::NONE, Key::Minus) {
+                -1.
+            } else {
+                0.
+            };
+            (pan, dir, tab, select, zoom)
+        });
+
+        if self.settings_navigation.zoom_and_pan_enabled {
+            if pan != Vec2::ZERO {
+                self.set_pan(meta.pan + pan * KEYBOARD_PAN_STEP, meta);
+            }
+            if zoom != 0. {
+                let step = self.settings_navigation.zoom_speed * zoom;
+                self.zoom(&resp.rect, step, None, meta);
+            }
+        }
+
+        let focused = self
+            .g
+            .focused_node()
+            .filter(|idx| self.g.node(*idx).is_some());
+        let next = match (focused, tab, dir) {
+            (_, Some(forward), _) => self.node_in_order(focused, forward),
+            (Some(idx), None, Some(dir)) => self.node_in_direction(idx, dir),
+            (None, None, Some(_)) => self.node_in_order(None, true),
+            _ => None,
+        };
+        if let Some(idx) = next {
+            self.set_focused_node(idx, resp, meta);
+        }
+
+        if select {
+            if let Some(idx) = self.g.focused_node() {
+                self.handle_node_click(idx);
+            }
+        }
+    }
+
+    /// Returns the node after or before the given one in the order of indices, wrapping around.
+    fn node_in_order(&self, from: Option<NodeIndex<Ix>>, forward: bool) -> Option<NodeIndex<Ix>> {
+        let mut indices = self.g.g.node_indices().collect::<Vec<_>>();
+        if !forward {
+            indices.reverse();
+        }
+
+        let pos = from.and_then(|from| indices.iter().position(|idx| *idx == from));
+        match pos {
+            Some(pos) => indices.get(pos + 1).or(indices.first()).copied(),
+            None => indices.first().copied(),
+        }
+    }
+
+    /// Returns the closest node which lies within 45 degrees of the direction from the given node.
+    fn node_in_direction(&self, from: NodeIndex<Ix>, dir: Vec2) -> Option<NodeIndex<Ix>> {
+        let origin = self.g.node(from)?.location();
+        self.g
+            .nodes_iter()
+            .filter(|(idx, _)| *idx != from)
+            .filter_map(|(idx, n)| {
+                let offset = n.location() - origin;
+                let along = offset.dot(dir);
+                let across = (offset - dir * along).length();
+                (along > 0. && across <= along).then_some((idx, offset.length()))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Focuses the node and pans the graph to it if it is out of the screen.
+    fn set_focused_node(&mut self, idx: NodeIndex<Ix>, resp: &Response, meta: &mut Metadata) {
+        if let Some(n) = self.g.focused_node().and_then(|prev| self.g.node_mut(prev)) {
+            n.set_focused(false);
+        }
+        let Some(n) = self.g.node_mut(idx) else {
+            return;
+        };
+        n.set_focused(true);
+        let location = n.location();
+        self.g.set_focused_node(Some(idx));
+
+        let screen_pos = meta.canvas_to_screen_pos(location);
+        if !resp.rect.contains(screen_pos) {
+            self.set_pan(meta.pan + (resp.rect.center() - screen_pos), meta);
+        }
+    }
+
+    /// Describes nodes and edges for screen readers.
+    #[cfg(feature = "accesskit")]
+    fn update_accesskit(&self, ui: &Ui, resp: &Response, meta: &Metadata) {
+        use egui::accesskit::{NodeBuilder, Rect as AccessRect, Role};
+
+        let set_bounds = |builder: &mut NodeBuilder, rect: Rect| {
+            builder.set_bounds(AccessRect {
+                x0: rect.min.x.into(),
+                y0: rect.min.y.into(),
+                x1: rect.max.x.into(),
+                y1: rect.max.y.into(),
+            });
+        };
+
+        let ctx = ui.ctx();
+        let enabled = ctx.accesskit_node_builder(resp.id, |builder| {
+            builder.set_role(Role::Group);
+            builder.set_name("Graph");
+        });
+        if enabled.is_none() {
+            return;
+        }
+
+        ctx.with_accessibility_parent(resp.id, || {
+            for (idx, n) in self.g.nodes_iter() {
+                ctx.accesskit_node_builder(resp.id.with(("node", idx.index())), |builder| {
+                    builder.set_role(Role::ListItem);
+                    builder.set_name(n.label());
+                    builder.set_selected(n.selected());
+                    let center = meta.canvas_to_screen_pos(n.location());
+                    let size = meta.canvas_to_screen_size(node_size(n, Vec2::X));
+                    set_bounds(
+                        builder,
+                        Rect::from_center_size(center, Vec2::splat(size * 2.)),
+                    );
+                });
+            }
+
+            for (idx, e) in self.g.edges_iter() {
+                let Some((start, end)) = self.g.edge_endpoints(idx) else {
+                    continue;
+                };
+                let (Some(start), Some(end)) = (self.g.node(start), self.g.node(end)) else {
+                    continue;
+                };
+                ctx.accesskit_node_builder(resp.id.with(("edge", idx.index())), |builder| {
+                    builder.set_role(Role::Link);
+                    builder.set_name(format!(
+                        "{} from {} to {}",
+                        e.label(),
+                        start.label(),
+                        end.label()
+                    ));
+                    builder.set_selected(e.selected());
+                    set_bounds(
+                        builder,
+                        Rect::from_two_pos(
+                            meta.canvas_to_screen_pos(start.location()),
+                            meta.canvas_to_screen_pos(end.location()),
+                        ),
+                    );
+                });
+            }
+        });
+    }
+
+    /// Updates hovered node and edge. Nodes are drawn over edges, so a hovered node hides edges.
+    fn handle_hover(&mut self, resp: &Response, meta: &Metadata) {
+        let pos = resp.hover_pos();
+        let node = pos.and_then(|pos| self.g.node_by_screen_pos(meta, pos));
+        let edge = match node {
+            Some(_) => None,
+            None => pos.and_then(|pos| self.g.edge_by_screen_pos(meta, pos)),
+        };
+
+        let prev_node = self.g.hovered_node();
+        if prev_node != node {
+            if let Some(idx) = prev_node {
+                self.set_node_hovered(idx, false);
+            }
+            if let Some(idx) = node {
+                self.set_node_hovered(idx, true);
+            }
+            self.g.set_hovered_node(node);
+        }
+
+        let prev_edge = self.g.hovered_edge();
+        if prev_edge != edge {
+            if let Some(idx) = prev_edge {
+                self.set_edge_hovered(idx, false);
+            }
+            if let Some(idx) = edge {
+                self.set_edge_hovered(idx, true);
+            }
+            self.g.set_hovered_edge(edge);
+        }
+    }
+
+    /// Highlights the neighborhood of the hovered node or of the selected nodes.
+    fn handle_highlight(&mut self) {
+        let (nodes, edges) = if self.settings_style.highlight_neighbors_enabled {
+            let sources = match self.g.hovered_node() {
+                Some(idx) => vec![idx],
+                None => self.g.selected_nodes().to_vec(),
+            };
+            self.g.neighborhood(
+                &sources,
+                self.settings_style.highlight_depth,
+                self.settings_style.highlight_direction,
+            )
+        } else {
+            (vec![], vec![])
+        };
+
+        if nodes.is_empty() && self.g.highlighted_nodes().is_empty() {
+            return;
+        }
+
+        let highlight_nodes: HashSet<_> = nodes.iter().copied().collect();
+        let highlight_edges: HashSet<_> = edges.iter().copied().collect();
+        let highlight = |highlighted: bool| match (highlighted, nodes.is_empty()) {
+            (_, true) => Highlight::None,
+            (true, false) => Highlight::Emphasized,
+            (false, false) => Highlight::Dimmed,
+        };
+
+        self.g
+            .g
+            .node_indices()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|idx| {
+                let h = highlight(highlight_nodes.contains(&idx));
+                self.g.node_mut(idx).unwrap().set_highlight(h);
+            });
+        self.g
+            .g
+            .edge_indices()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|idx| {
+                let h = highlight(highlight_edges.contains(&idx));
+                self.g.edge_mut(idx).unwrap().set_highlight(h);
+            });
+
+        self.g.set_highlighted_nodes(nodes);
+        self.g.set_highlighted_edges(edges);
+    }
+
+    fn set_node_hovered(&mut self, idx: NodeIndex<Ix>, hovered: bool) {
+        let Some(n) = self.g.node_mut(idx) else {
+            return;
+        };
+        n.set_hovered(hovered);
+
+        #[cfg(feature = "events")]
+        self.publish_event(if hovered {
+            Event::NodeHoverEnter(PayloadNodeHoverEnter { id: idx.index() })
+        } else {
+            Event::NodeHoverLeave(PayloadNodeHoverLeave { id: idx.index() })
+        });
+    }
+
+    fn set_edge_hovered(&mut self, idx: EdgeIndex<Ix>, hovered: bool) {
+        let Some(e) = self.g.edge_mut(idx) else {
+            return;
+        };
+        e.set_hovered(hovered);
+
+        #[cfg(feature = "events")]
+        self.publish_event(if hovered {
+            Event::EdgeHoverEnter(PayloadEdgeHoverEnter { id: idx.index() })
+        } else {
+            Event::EdgeHoverLeave(PayloadEdgeHoverLeave { id: idx.index() })
+        });
+    }
+
+    fn show_tooltip(&mut self, resp: &Response) {
+        if let (Some(idx), Some(tooltip)) = (self.g.hovered_node(), self.node_tooltip.as_mut()) {
+            if let Some(n) = self.g.node(idx) {
+                resp.clone().on_hover_ui_at_pointer(|ui| tooltip(ui, n));
+            }
+        }
+        if let (Some(idx), Some(tooltip)) = (self.g.hovered_edge(), self.edge_tooltip.as_mut()) {
+            if let Some(e) = self.g.edge(idx) {
+                resp.clone().on_hover_ui_at_pointer(|ui| tooltip(ui, e));
+            }
+        }
+    }
+
+    /// Remembers the element under the pointer on secondary click and shows the context menu for it.
+    fn handle_context_menu(&mut self, resp: &Response, meta: &mut Metadata) {
+        if resp.secondary_clicked() {
+            if let Some(pos) = resp.interact_pointer_pos() {
+                let target = if let Some(idx) = self.g.node_by_screen_pos(meta, pos) {
+                    MenuTarget::Node(idx.index())
+                } else if let Some(idx) = self.g.edge_by_screen_pos(meta, pos) {
+                    MenuTarget::Edge(idx.index())
+                } else {
+                    MenuTarget::Canvas(meta.screen_to_canvas_pos(pos))
+                };
+                meta.context_menu = Some(target);
+
+                #[cfg(feature = "events")]
+                self.publish_event(match target {
+                    MenuTarget::Node(id) => {
+                        Event::NodeSecondaryClick(PayloadNodeSecondaryClick { id })
+                    }
+                    MenuTarget::Edge(id) => {
+                        Event::EdgeSecondaryClick(PayloadEdgeSecondaryClick { id })
+                    }
+                    MenuTarget::Canvas(pos) => {
+                        Event::CanvasSecondaryClick(PayloadCanvasSecondaryClick { pos: pos.into() })
+                    }
+                });
+            }
+        }
+
+        let (Some(context_menu), Some(target)) = (self.context_menu.as_mut(), meta.context_menu)
+        else {
+            return;
+        };
+        let target = match target {
+            MenuTarget::Node(idx) => ContextMenuTarget::Node(NodeIndex::new(idx)),
+            MenuTarget::Edge(idx) => ContextMenuTarget::Edge(EdgeIndex::new(idx)),
+            MenuTarget::Canvas(pos) => ContextMenuTarget::Canvas(pos),
+        };
+        if resp.context_menu(|ui| context_menu(ui, target)).is_none() {
+            meta.context_menu = None;
+        }
+    }
+
+    fn create_node(&mut self, location: Pos2) {
+        let Some(factory) = self.node_factory.as_mut() else {
+            return;
+        };
+        let Some(payload) = factory(location) else {
+            return;
+        };
+
+        let idx = self.g.add_node_with_location(payload, location);
+
+        #[cfg(feature = "events")]
+        self.publish_event(Event::NodeCreate(PayloadNodeCreate { id: idx.index() }));
+        #[cfg(not(feature = "events"))]
+        let _ = idx;
+    }
+
+    fn remove_selected(&mut self) {
+        let selected_edges = self.g.selected_edges().to_vec();
+        for idx in selected_edges {
+            if self.g.remove_edge(idx).is_some() {
+                #[cfg(feature = "events")]
+                self.publish_event(Event::EdgeRemove(PayloadEdgeRemove { id: idx.index() }));
+            }
+        }
+
+        let selected_nodes = self.g.selected_nodes().to_vec();
+        for idx in selected_nodes {
+            if self.g.remove_node(idx).is_some() {
+                #[cfg(feature = "events")]
+                self.publish_event(Event::NodeRemove(PayloadNodeRemove { id: idx.index() }));
+            }
+        }
+
+        self.g.set_selected_edges(vec![]);
+        self.g.set_selected_nodes(vec![]);
+        if let Some(dragged) = self.g.dragged_node() {
+            if self.g.node(dragged).is_none() {
+                self.g.set_dragged_node(None);
+            }
+        }
+    }
+
+    fn draw_edge_draft(&self, ui: &Ui, painter: &Painter, meta: &Metadata) {
+        let Some(draft) = &meta.edge_draft else {
+            return;
+        };
+        let Some(anchor) = self.g.node(NodeIndex::new(draft.anchor)) else {
+            return;
+        };
+
+        let dir = (draft.end - anchor.location()).normalized();
+        let mut start = meta.canvas_to_screen_pos(anchor.display().closest_boundary_point(dir));
+        let mut end = meta.canvas_to_screen_pos(draft.end);
+        if matches!(draft.reconnect, Some((_, true))) {
+            std::mem::swap(&mut start, &mut end);
+        }
+
+        let stroke = ui.visuals().selection.stroke;
+        if self.g.is_directed() {
+            painter.arrow(start, end - start, stroke);
+        } else {
+            painter.line_segment([start, end], stroke);
+        }
+    }
+
+    fn draw_edge_handles(&self, ui: &Ui, painter: &Painter, meta: &Metadata) {
+        if !self.settings_interaction.editing_enabled || meta.edge_draft.is_some() {
+            return;
+        }
+
+        let visuals = &ui.visuals().selection;
+        for idx in self.g.selected_edges() {
+            let Some((start, end)) = self.edge_handles(*idx) else {
+                continue;
+            };
+            for handle in [start, end] {
+                painter.circle(
+                    meta.canvas_to_screen_pos(handle),
+                    EDGE_HANDLE_RADIUS,
+                    visuals.bg_fill,
+                    visuals.stroke,
+                );
+            }
+        }
+    }
+
+    fn apply_selection_area(&mut self, area: &SelectionArea) {
+        let bounds = area.bounds();
+        let nodes_inside = self
+            .g
+            .nodes_in_rect(bounds)
+            .into_iter()
+            .filter(|idx| self.g.node(*idx).is_some_and(|n| area.intersects_node(n)))
+            .collect::<HashSet<_>>();
+
+        let mut edges_inside = HashSet::new();
+        if self.settings_interaction.box_selection_edges_enabled {
+            let is_inside = |idx| {
+                self.g
+                    .node(idx)
+                    .is_some_and(|n| area.contains(n.location()))
+            };
+            edges_inside = self
+                .g
+                .edges_in_rect(bounds)
+                .into_iter()
+                .filter(|idx| {
+                    self.g
+                        .edge_endpoints(*idx)
+                        .is_some_and(|(start, end)| is_inside(start) && is_inside(end))
+                })
+                .collect::<HashSet<_>>();
+        }
+
+        if area.mode == SelectionMode::Replace {
+            let selected_nodes = self.g.selected_nodes().to_vec();
+            for idx in selected_nodes {
+                if !nodes_inside.contains(&idx) {
+                    self.deselect_node(idx);
+                }
+            }
+            let selected_edges = self.g.selected_edges().to_vec();
+            for idx in selected_edges {
+                if !edges_inside.contains(&idx) {
+                    self.deselect_edge(idx);
+                }
+            }
+        }
+
+        let select = area.mode != SelectionMode::Remove;
+        for idx in nodes_inside {
+            if self.g.node(idx).is_some_and(|n| n.selected() != select) {
+                if select {
+                    self.select_node(idx);
+                } else {
+                    self.deselect_node(idx);
+                }
+            }
+        }
+        for idx in edges_inside {
+            if self.g.edge(idx).is_some_and(|e| e.selected() != select) {
+                if select {
+                    self.select_edge(idx);
+                } else {
+                    self.deselect_edge(idx);
+                }
+            }
+        }
+    }
+
+    fn draw_selection_area(ui: &Ui, painter: &Painter, meta: &Metadata) {
+        let Some(area) = &meta.selection else {
+            return;
+        };
+
+        let visuals = &ui.visuals().selection;
+        match &area.shape {
+            SelectionShape::Rect { start, end } => {
+                let rect = Rect::from_two_pos(
+                    meta.canvas_to_screen_pos(*start),
+                    meta.canvas_to_screen_pos(*end),
+                );
+                painter.rect(
+                    rect,
+                    0.,
+                    visuals.bg_fill.gamma_multiply(0.25),
+                    visuals.stroke,
+                );
+            }
+            SelectionShape::Lasso(path) => {
+                let points = path
+                    .iter()
+                    .map(|p| meta.canvas_to_screen_pos(*p))
+                    .collect::<Vec<_>>();
+                painter.add(Shape::closed_line(points, visuals.stroke));
+            }
+        }
+    }
+
+    fn handle_node_drag(&mut self, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_interaction.dragging_enabled {
+            return;
+        }
+
+        if !resp.dragged_by(PointerButton::Primary)
+            && !resp.drag_started_by(PointerButton::Primary)
+            && !resp.drag_stopped_by(PointerButton::Primary)
+        {
+            return;
+        }
+
+        if resp.drag_started() && meta.edge_draft.is_none() {
+            if let Some(idx) = self.g.node_by_screen_pos(meta, resp.hover_pos().unwrap()) {
+                self.set_drag_start(idx);
+            }
+        }
+
+        // handle mouse drag
+        if resp.dragged()
+            && self.g.dragged_node().is_some()
+            && (resp.drag_delta().x.abs() > 0. || resp.drag_delta().y.abs() > 0.)
+        {
+            let n_idx_dragged = self.g.dragged_node().unwrap();
+            let delta_in_graph_coords = resp.drag_delta() / meta.zoom;
+            self.move_dragged_nodes(n_idx_dragged, delta_in_graph_coords);
+        }
+
+        // compensate movement of the node which is not caused by dragging
+        if let Some(n_idx_dragged) = self.g.dragged_node() {
+            if let Some(mouse_pos) = resp.hover_pos() {
+                if let Some(node) = self.g.node(n_idx_dragged) {
+                    let node_pos = node.location() * meta.zoom + meta.pan;
+                    let delta = mouse_pos - node_pos;
+
+                    self.move_dragged_nodes(n_idx_dragged, delta / meta.zoom);
+                }
+            }
+        }
+
+        if resp.drag_stopped() && self.g.dragged_node().is_some() {
+            let n_idx = self.g.dragged_node().unwrap();
+            self.set_drag_end(n_idx);
+        }
+    }
+
+    fn handle_undo_redo(&mut self, ui: &Ui, resp: &Response) {
+        if !self.settings_interaction.undo_redo_keys_enabled
+            || !resp.hovered()
+            || ui.ctx().wants_keyboard_input()
+        {
+            return;
+        }
+
+        let (undo, redo) = ui.input_mut(|i| {
+            // shift variant goes first because consuming ignores extra modifiers
+            let redo = i.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z)
+                || i.consume_key(Modifiers::COMMAND, Key::Y);
+            let undo = i.consume_key(Modifiers::COMMAND, Key::Z);
+            (undo, redo)
+        });
+        if undo && self.g.undo() {
+            #[cfg(feature = "events")]
+            self.publish_history_events(self.g.history.last_redo());
+        }
+        if redo && self.g.redo() {
+            #[cfg(feature = "events")]
+            self.publish_history_events(self.g.history.last_undo());
+        }
+    }
+
+    /// Publishes events for the changes applied by undo or redo. The changes are described by
+    /// the step which reverts them.
+    #[cfg(feature = "events")]
+    fn publish_history_events(&self, revert: Option<&crate::history::Step<N, E, Ty, Ix, Dn, De>>) {
+        for change in revert.into_iter().flatten() {
+            match change {
+                Change::RemoveNode(idx) => {
+                    self.publish_event(Event::NodeCreate(PayloadNodeCreate { id: idx.index() }));
+                }
+                Change::AddNode(idx, _) => {
+                    self.publish_event(Event::NodeRemove(PayloadNodeRemove { id: idx.index() }));
+                }
+                Change::RemoveEdge(idx) | Change::ReconnectEdge(idx, _, _) => {
+                    let Some((source, target)) = self.g.edge_endpoints(*idx) else {
+                        continue;
+                    };
+                    let (id, source, target) = (idx.index(), source.index(), target.index());
+                    self.publish_event(if matches!(change, Change::RemoveEdge(_)) {
+                        Event::EdgeCreate(PayloadEdgeCreate { id, source, target })
+                    } else {
+                        Event::EdgeReconnect(PayloadEdgeReconnect { id, source, target })
+                    });
+                }
+                Change::AddEdge(idx, ..) => {
+                    self.publish_event(Event::EdgeRemove(PayloadEdgeRemove { id: idx.index() }));
+                }
+                Change::NodeProps(idx, old) => {
+                    let Some(n) = self.g.node(*idx) else {
+                        continue;
+                    };
+                    if n.label() != old.label {
+                        self.publish_event(Event::NodeLabelChange(PayloadNodeLabelChange {
+                            id: idx.index(),
+                            label: n.label(),
+                        }));
+                    }
+                    self.publish_node_move(*idx, old.location());
+                }
+                Change::EdgeProps(idx, old) => {
+                    let Some(e) = self.g.edge(*idx) else {
+                        continue;
+                    };
+                    if e.label() != old.label {
+                        self.publish_event(Event::EdgeLabelChange(PayloadEdgeLabelChange {
+                            id: idx.index(),
+                            label: e.label(),
+                        }));
+                    }
+                }
+                Change::Location(idx, old) => {
+                    let Some(n) = self.g.node(*idx) else {
+                        continue;
+                    };
+                    self.publish_node_move(*idx, old.unwrap_or(n.props().location));
+                }
+            }
+        }
+    }
+
+    /// Publishes the move event if the node is not at the old location anymore.
+    #[cfg(feature = "events")]
+    fn publish_node_move(&self, idx: NodeIndex<Ix>, old: Pos2) {
+        let Some(new_pos) = self.g.node(idx).map(Node::location) else {
+            return;
+        };
+        if new_pos != old {
+            self.publish_event(Event::NodeMove(PayloadNodeMove {
+                id: idx.index(),
+                diff: (new_pos - old).into(),
+                new_pos: [new_pos.x, new_pos.y],
+            }));
+        }
+    }
+
+    fn fit_to_screen(&self, rect: &Rect, meta: &mut Metadata) {
+        let (new_zoom, new_pan) = self.fit_bounds(rect, meta.graph_bounds());
+
+        // calculate the zoom delta and call handle_zoom to adjust the zoom factor
+        let zoom_delta = new_zoom / meta.zoom - 1.0;
+        self.zoom(rect, zoom_delta, None, meta);
+        self.set_pan(new_pan, meta);
+    }
+
+    /// Returns zoom and pan which fit the bounds in the canvas rect.
+    fn fit_bounds(&self, rect: &Rect, bounds: Rect) -> (f32, Vec2) {
+        // calculate graph dimensions with decorative padding
+        let mut diag = bounds.max - bounds.min;
+
+        // if the graph is empty or consists from one node, use a default size
+        if diag == Vec2::ZERO {
+            diag = Vec2::new(1., 100.);
+        }
+
+        let graph_size = diag * (1. + self.settings_navigation.screen_padding);
+        let (width, height) = (graph_size.x, graph_size.y);
+
+        // calculate canvas dimensions
+        let canvas_size = rect.size();
+        let (canvas_width, canvas_height) = (canvas_size.x, canvas_size.y);
+
+        // calculate zoom factors for x and y to fit the graph inside the canvas
+        let zoom_x = canvas_width / width;
+        let zoom_y = canvas_height / height;
+
+        // choose the minimum of the two zoom factors to avoid distortion
+        let new_zoom = self.clamp_zoom(zoom_x.min(zoom_y));
+
+        // calculate the center of the graph and the canvas
+        let graph_center = (bounds.min.to_vec2() + bounds.max.to_vec2()) / 2.0;
+
+        // adjust the pan value to align the centers of the graph and the canvas
+        let new_pan = rect.center().to_vec2() - graph_center * new_zoom;
+        (new_zoom, new_pan)
+    }
+
+    /// Applies camera requests made with [`Camera`] and advances the camera animation.
+    fn handle_camera(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        let top_left = meta.top_left.to_vec2();
+        if let Some(request) = Camera::take_request(ui.ctx(), resp.id) {
+            if let Some((zoom, pan)) = self.camera_target(&request.target, &resp.rect, meta) {
+                if request.duration > 0. {
+                    meta.camera_animation = Some(CameraAnimation {
+                        from_zoom: meta.zoom,
+                        from_pan: meta.pan - top_left,
+                        to_zoom: zoom,
+                        to_pan: pan - top_left,
+                        start: ui.input(|i| i.time),
+                        duration: request.duration,
+                    });
+                } else {
+                    meta.camera_animation = None;
+                    self.set_zoom(zoom, meta);
+                    self.set_pan(pan, meta);
+                }
+            }
+        }
+
+        let Some(animation) = meta.camera_animation.as_ref() else {
+            return;
+        };
+
+        // user navigation interrupts the animation
+        let interrupted = resp.dragged() || ui.input(|i| i.zoom_delta() != 1.);
+        if interrupted {
+            meta.camera_animation = None;
+            return;
+        }
+
+        let (zoom, pan, finished) = animation.at(ui.input(|i| i.time));
+        if finished {
+            meta.camera_animation = None;
+        }
+        self.set_zoom(zoom, meta);
+        self.set_pan(pan + top_left, meta);
+    }
+
+    /// Returns zoom and pan for the camera target or None if the target can not be reached.
+    fn camera_target(
+        &self,
+        target: &CameraTarget,
+        rect: &Rect,
+        meta: &Metadata,
+    ) -> Option<(f32, Vec2)> {
+        let center = rect.center().to_vec2();
+        match target {
+            CameraTarget::Node(idx) => {
+                let location = self.g.node(NodeIndex::new(*idx))?.location();
+                Some((meta.zoom, center - location.to_vec2() * meta.zoom))
+            }
+            CameraTarget::Nodes(nodes) => {
+                let bounds = nodes
+                    .iter()
+                    .filter_map(|idx| self.g.node(NodeIndex::new(*idx)))
+                    .map(|n| {
+                        let size = node_size(n, Vec2::new(0., 1.));
+                        Rect::from_center_size(n.location(), Vec2::splat(size * 2.))
+                    })
+                    .reduce(Rect::union)?;
+                Some(self.fit_bounds(rect, bounds))
+            }
+            CameraTarget::All => {
+                if self.g.node_count() == 0 {
+                    return None;
+                }
+                Some(self.fit_bounds(rect, meta.graph_bounds()))
+            }
+            CameraTarget::Zoom(zoom) => {
+                if *zoom <= 0. {
+                    return None;
+                }
+                let zoom = self.clamp_zoom(*zoom);
+                let graph_center = (center - meta.pan) / meta.zoom;
+                Some((zoom, center - graph_center * zoom))
+            }
+            CameraTarget::Pan(pan) => Some((meta.zoom, *pan + meta.top_left.to_vec2())),
+        }
+    }
+
+    fn handle_navigation(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !meta.first_frame {
+            meta.pan += resp.rect.left_top() - meta.top_left;
+        }
+        meta.top_left = resp.rect.left_top();
+
+        self.handle_zoom(ui, resp, meta);
+        self.handle_pan(ui, resp, meta);
+    }
+
+    fn handle_zoom(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_navigation.zoom_and_pan_enabled {
+            return;
+        }
+
+        ui.input(|i| {
+            let delta = i.zoom_delta();
+            if delta != 1. {
+                let step = self.settings_navigation.zoom_speed * (delta - 1.).signum();
+                self.zoom(&resp.rect, step, i.pointer.hover_pos(), meta);
+                return;
+            }
+
+            let scroll = i.smooth_scroll_delta.y;
+            if self.settings_navigation.scroll_zoom_enabled && resp.hovered() && scroll != 0. {
+                let step = self.settings_navigation.zoom_speed * scroll / SCROLL_ZOOM_STEP;
+                self.zoom(&resp.rect, step, i.pointer.hover_pos(), meta);
+            }
+        });
+    }
+
+    fn handle_pan(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_navigation.zoom_and_pan_enabled {
+            return;
+        }
+
+        if self.settings_navigation.scroll_pan_enabled
+            && !self.settings_navigation.scroll_zoom_enabled
+            && resp.hovered()
+        {
+            let scroll = ui.input(|i| i.smooth_scroll_delta);
+            if scroll != Vec2::ZERO {
+                self.set_pan(meta.pan + scroll, meta);
+            }
+        }
+
+        if (resp.dragged_by(PointerButton::Middle) || resp.dragged_by(PointerButton::Primary))
+            && self.g.dragged_node().is_none()
+            && meta.selection.is_none()
+            && meta.edge_draft.is_none()
+            && (resp.drag_delta().x.abs() > 0. || resp.drag_delta().y.abs() > 0.)
+        {
+            let new_pan = meta.pan + resp.drag_delta();
+            self.set_pan(new_pan, meta);
+        }
+    }
+
+    /// Zooms the graph by the given delta. It also compensates with pan to keep the zoom center in the same place.
+    fn zoom(&self, rect: &Rect, delta: f32, zoom_center: Option<Pos2>, meta: &mut Metadata) {
+        let center_pos = zoom_center.unwrap_or(rect.center()).to_vec2();
+        let graph_center_pos = (center_pos - meta.pan) / meta.zoom;
+        let factor = 1. + delta;
+        let new_zoom = self.clamp_zoom(meta.zoom * factor);
+        if new_zoom == meta.zoom {
+            return;
+        }
+
+        let pan_delta = graph_center_pos * meta.zoom - graph_center_pos * new_zoom;
+        let new_pan = meta.pan + pan_delta;
+
+        self.set_pan(new_pan, meta);
+        self.set_zoom(new_zoom, meta);
+    }
+
+    /// Draws the minimap and centers the view at the clicked or dragged minimap position.
+    fn handle_minimap(&self, ui: &Ui, resp: &Response, painter: &Painter, meta: &mut Metadata) {
+        if !self.settings_navigation.minimap_enabled || self.g.node_count() == 0 {
+            return;
+        }
+
+        let minimap = Minimap::new(
+            resp.rect,
+            meta.graph_bounds(),
+            self.settings_navigation.minimap_size,
+        );
+        if !minimap.is_valid() {
+            return;
+        }
+
+        let minimap_resp = ui.interact(
+            minimap.rect,
+            resp.id.with("minimap"),
+            Sense::click_and_drag(),
+        );
+        if let Some(pos) = minimap_resp.interact_pointer_pos() {
+            let target = minimap.minimap_to_canvas(pos);
+            meta.camera_animation = None;
+            self.set_pan(
+                resp.rect.center().to_vec2() - target.to_vec2() * meta.zoom,
+                meta,
+            );
+        }
+
+        let viewport = Rect::from_min_max(
+            meta.screen_to_canvas_pos(resp.rect.min),
+            meta.screen_to_canvas_pos(resp.rect.max),
+        );
+        minimap.draw(ui, painter, self.g, viewport);
+    }
+
+    fn clamp_zoom(&self, zoom: f32) -> f32 {
+        zoom.clamp(
+            self.settings_navigation.zoom_min,
+            self.settings_navigation.zoom_max,
+        )
+    }
+
+    /// Pans the graph back to the screen if it is panned out of it.
+    fn handle_pan_bounds(&self, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_navigation.pan_bounds_enabled || self.g.node_count() == 0 {
+            return;
+        }
+
+        let bounds = meta.graph_bounds();
+        let screen_bounds = Rect::from_min_max(
+            meta.canvas_to_screen_pos(bounds.min),
+            meta.canvas_to_screen_pos(bounds.max),
+        );
+        let delta = pan_into_view(resp.rect, screen_bounds, PAN_BOUNDS_MARGIN);
+        if delta != Vec2::ZERO {
+            self.set_pan(meta.pan + delta, meta);
+        }
+    }
+
+    fn select_node(&mut self, idx: NodeIndex<Ix>) {
+        let n = self.g.node_mut(idx).unwrap();
+        n.set_selected(true);
+
+        #[cfg(feature = "events")]
+        self.publish_event(Event::NodeSelect(PayloadNodeSelect { id: idx.index() }));
+    }
+
+    fn deselect_node(&mut self, idx: NodeIndex<Ix>) {
+        let n = self.g.node_mut(idx).unwrap();
+        n.set_selected(false);
+
+        #[cfg(feature = "events")]
+        self.publish_event(Event::NodeDeselect(PayloadNodeDeselect { id: idx.index() }));
+    }
+
+    #[allow(unused_variables, clippy::unused_self)]
+    fn set_node_clicked(&self, idx: NodeIndex<Ix>) {
+        #[cfg(feature = "events")]
+        self.publish_event(Event::NodeClick(PayloadNodeClick { id: idx.index() }));
+    }
+
+    #[allow(unused_variables, clippy::unused_self)]
+    fn set_node_double_clicked(&self, idx: NodeIndex<Ix>) {
+        #[cfg(feature = "events")]
+        self.publish_event(Event::NodeDoubleClick(PayloadNodeDoubleClick {
+            id: idx.index(),
+        }));
+    }
+
+    #[allow(unused_variables, clippy::unused_self)]
+    fn set_edge_clicked(&self, idx: EdgeIndex<Ix>) {
+        #[cfg(feature = "events")]
+        self.publish_event(Event::EdgeClick(PayloadEdgeClick { id: idx.index() }));
+    }
+
+    fn select_edge(&mut self, idx: EdgeIndex<Ix>) {
+        let e = self.g.edge_mut(idx).unwrap();
+        e.set_selected(true);
+
+        #[cfg(feature = "events")]
+        self.publish_event(Event::EdgeSelect(PayloadEdgeSelect { id: idx.index() }));
+    }
+
+    fn deselect_edge(&mut self, idx: EdgeIndex<Ix>) {
+        let e = self.g.edge_mut(idx).unwrap();
+        e.set_selected(false);
+
+        #[cfg(feature = "events")]
+        self.publish_event(Event::EdgeDeselect(PayloadEdgeDeselect { id: idx.index() }));
+    }
+
+    /// Deselects all nodes AND edges.
+    fn deselect_all(&mut self) {
+        self.deselect_all_nodes();
+        self.deselect_all_edges();
+    }
+
+    fn deselect_all_nodes(&mut self) {
+        let selected_nodes = self.g.selected_nodes().to_vec();
         for idx in selected_nodes {
             self.deselect_node(idx);
         }
@@ -590,7 +2072,36 @@ where
         }));
     }
 
+    /// Moves the dragged node. If the dragged node is selected, all the selected nodes are moved
+    /// by the same delta so the selection keeps its shape.
+    fn move_dragged_nodes(&mut self, dragged: NodeIndex<Ix>, delta: Vec2) {
+        if delta == Vec2::ZERO {
+            return;
+        }
+
+        if !self.g.selected_nodes().contains(&dragged) {
+            self.move_node(dragged, delta);
+            return;
+        }
+
+        let selected_nodes = self.g.selected_nodes().to_vec();
+        for idx in selected_nodes {
+            self.move_node(idx, delta);
+        }
+    }
+
     fn set_drag_start(&mut self, idx: NodeIndex<Ix>) {
+        let moved = if self.g.selected_nodes().contains(&idx) {
+            self.g.selected_nodes().to_vec()
+        } else {
+            vec![idx]
+        };
+        let start = moved
+            .into_iter()
+            .filter_map(|idx| Some((idx, self.g.node(idx)?.props().location_user)))
+            .collect::<Vec<_>>();
+        self.g.history.begin_drag(start.into_iter());
+
         let n = self.g.node_mut(idx).unwrap();
         n.set_dragged(true);
 
@@ -604,6 +2115,16 @@ where
         let n = self.g.node_mut(idx).unwrap();
         n.set_dragged(false);
 
+        for (moved, location) in self.g.history.end_drag() {
+            if self
+                .g
+                .node(moved)
+                .is_some_and(|n| n.props().location_user != location)
+            {
+                self.g.history.record(|| Change::Location(moved, location));
+            }
+        }
+
         #[cfg(feature = "events")]
         self.publish_event(Event::NodeDragEnd(PayloadNodeDragEnd { id: idx.index() }));
     }
@@ -636,3 +2157,431 @@ where
         }
     }
 }
+
+/// Returns the shift which makes at least `margin` of the bounds visible in the view
+/// along each axis, or the whole bounds if they are smaller than the margin.
+fn pan_into_view(view: Rect, bounds: Rect, margin: f32) -> Vec2 {
+    let shift = |view_min: f32, view_max: f32, min: f32, max: f32| {
+        let margin = margin.min(max - min);
+        if max < view_min + margin {
+            view_min + margin - max
+        } else if min > view_max - margin {
+            view_max - margin - min
+        } else {
+            0.
+        }
+    };
+
+    Vec2::new(
+        shift(view.min.x, view.max.x, bounds.min.x, bounds.max.x),
+        shift(view.min.y, view.max.y, bounds.min.y, bounds.max.y),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pan_into_view() {
+        let view = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let inside = Rect::from_min_size(Pos2::new(10., 10.), Vec2::splat(20.));
+        assert_eq!(pan_into_view(view, inside, 50.), Vec2::ZERO);
+
+        let left = Rect::from_min_size(Pos2::new(-300., 40.), Vec2::splat(100.));
+        assert_eq!(pan_into_view(view, left, 50.), Vec2::new(250., 0.));
+
+        let below = Rect::from_min_size(Pos2::new(40., 150.), Vec2::splat(10.));
+        assert_eq!(pan_into_view(view, below, 50.), Vec2::new(0., -60.));
+    }
+
+    #[test]
+    fn test_zoom_limits() {
+        let mut graph = crate::random_graph(5, 5);
+        let clamp = |graph: &mut Graph, min: f32, max: f32, zoom: f32| {
+            let navigation = SettingsNavigation::new().with_zoom_limits(min, max);
+            DefaultGraphView::new(graph)
+                .with_navigations(&navigation)
+                .clamp_zoom(zoom)
+        };
+
+        assert_eq!(clamp(&mut graph, 0.5, 2., 3.), 2.);
+        assert_eq!(clamp(&mut graph, 0.5, 2., 0.1), 0.5);
+        assert_eq!(clamp(&mut graph, f32::NAN, 2., 3.), 2.);
+        assert_eq!(clamp(&mut graph, 0.5, f32::NAN, 1e6), 1e6);
+        assert_eq!(clamp(&mut graph, 2., 0.5, 1.), 2.);
+        assert!(clamp(&mut graph, 0., 2., 0.) > 0.);
+        assert!(clamp(&mut graph, -1., f32::INFINITY, -5.) > 0.);
+        assert_eq!(
+            DefaultGraphView::new(&mut graph).clamp_zoom(1e-6),
+            1e-6,
+            "zoom is unbounded by default"
+        );
+    }
+
+    #[test]
+    fn test_idle_repaint() {
+        let mut graph = crate::random_graph(5, 5);
+        let ctx = egui::Context::default();
+        let mut repaint_requested = || {
+            let output = ctx.run(egui::RawInput::default(), |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.add(&mut DefaultGraphView::new(&mut graph));
+                });
+            });
+            output.viewport_output[&egui::ViewportId::ROOT].repaint_delay
+                != std::time::Duration::MAX
+        };
+
+        assert!(repaint_requested());
+        for _ in 0..5 {
+            repaint_requested();
+        }
+        assert!(!repaint_requested());
+    }
+
+    #[test]
+    fn test_camera_request() {
+        let mut graph = crate::random_graph(5, 5);
+        let navigation = SettingsNavigation::new().with_fit_to_screen_enabled(false);
+        let (a, b) = (Id::new("a"), Id::new("b"));
+
+        let ctx = egui::Context::default();
+        let run = |graph: &mut Graph, id: Id| {
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.add(
+                        &mut DefaultGraphView::new(graph)
+                            .with_id(id)
+                            .with_navigations(&navigation),
+                    );
+                });
+            });
+            Metadata::load_from_ctx(&ctx).zoom
+        };
+
+        let zoom = run(&mut graph, a);
+        Camera::new(&ctx, b).set_zoom(2.);
+        assert_eq!(run(&mut graph, a), zoom);
+        assert_eq!(run(&mut graph, b), 2.);
+
+        Camera::new(&ctx, a).set_zoom(3.);
+        assert_eq!(run(&mut graph, a), 3.);
+        assert!(Camera::take_request(&ctx, a).is_none());
+    }
+
+    #[test]
+    fn test_box_selection_modifiers() {
+        let mut graph: Graph = Graph::new(petgraph::stable_graph::StableGraph::default());
+        let a = graph.add_node_with_location((), Pos2::new(0., 0.));
+        let b = graph.add_node_with_location((), Pos2::new(100., 0.));
+        let settings = SettingsInteraction::new()
+            .with_box_selection_enabled(true)
+            .with_node_selection_enabled(true)
+            .with_node_selection_multi_enabled(true);
+
+        let ctx = egui::Context::default();
+        let run = |graph: &mut Graph, events: Vec<egui::Event>, modifiers: Modifiers| {
+            let input = egui::RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::new(800., 600.))),
+                events,
+                modifiers,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.add(&mut DefaultGraphView::new(graph).with_interactions(&settings));
+                });
+            });
+        };
+        // drags the box around the node b only
+        let drag = |graph: &mut Graph, modifiers: Modifiers| {
+            let meta = Metadata::load_from_ctx(&ctx);
+            let start = meta.canvas_to_screen_pos(Pos2::new(60., -30.));
+            let end = meta.canvas_to_screen_pos(Pos2::new(140., 30.));
+            let button = |pos, pressed| egui::Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed,
+                modifiers,
+            };
+            run(graph, vec![egui::Event::PointerMoved(start)], modifiers);
+            run(graph, vec![button(start, true)], modifiers);
+            run(graph, vec![egui::Event::PointerMoved(end)], modifiers);
+            run(graph, vec![button(end, false)], modifiers);
+            run(graph, vec![], Modifiers::NONE);
+        };
+
+        run(&mut graph, vec![], Modifiers::NONE);
+        run(&mut graph, vec![], Modifiers::NONE);
+        graph.g[a].set_selected(true);
+
+        drag(
+            &mut graph,
+            Modifiers::SHIFT | Modifiers::COMMAND | Modifiers::CTRL,
+        );
+        assert!(graph.g[a].selected() && graph.g[b].selected());
+
+        drag(&mut graph, Modifiers::SHIFT | Modifiers::ALT);
+        assert!(graph.g[a].selected() && !graph.g[b].selected());
+
+        drag(&mut graph, Modifiers::SHIFT);
+        assert!(!graph.g[a].selected() && graph.g[b].selected());
+    }
+
+    #[test]
+    fn test_edge_creation_modifiers() {
+        let mut graph: Graph = Graph::new(petgraph::stable_graph::StableGraph::default());
+        graph.add_node_with_location((), Pos2::new(0., 0.));
+        graph.add_node_with_location((), Pos2::new(100., 0.));
+        let settings = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_edge_creation_enabled(true)
+            .with_edge_creation_duplicates_enabled(true);
+
+        let ctx = egui::Context::default();
+        let run = |graph: &mut Graph, events: Vec<egui::Event>, modifiers: Modifiers| {
+            let input = egui::RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::new(800., 600.))),
+                events,
+                modifiers,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.add(
+                        &mut DefaultGraphView::new(graph)
+                            .with_interactions(&settings)
+                            .with_edge_factory(|_, _| Some(())),
+                    );
+                });
+            });
+        };
+        // drags from the node a to the node b
+        let drag = |graph: &mut Graph, modifiers: Modifiers| {
+            let meta = Metadata::load_from_ctx(&ctx);
+            let start = meta.canvas_to_screen_pos(Pos2::new(0., 0.));
+            let end = meta.canvas_to_screen_pos(Pos2::new(100., 0.));
+            let button = |pos, pressed| egui::Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed,
+                modifiers,
+            };
+            run(graph, vec![egui::Event::PointerMoved(start)], modifiers);
+            run(graph, vec![button(start, true)], modifiers);
+            run(graph, vec![egui::Event::PointerMoved(end)], modifiers);
+            run(graph, vec![button(end, false)], modifiers);
+            run(graph, vec![], Modifiers::NONE);
+        };
+
+        run(&mut graph, vec![], Modifiers::NONE);
+        run(&mut graph, vec![], Modifiers::NONE);
+
+        drag(&mut graph, Modifiers::SHIFT);
+        assert_eq!(graph.edge_count(), 1);
+
+        drag(
+            &mut graph,
+            Modifiers::SHIFT | Modifiers::COMMAND | Modifiers::CTRL,
+        );
+        assert_eq!(graph.edge_count(), 2);
+
+        drag(&mut graph, Modifiers::COMMAND | Modifiers::CTRL);
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_delete_with_focus() {
+        let mut graph: Graph = Graph::new(petgraph::stable_graph::StableGraph::default());
+        let a = graph.add_node_with_location((), Pos2::new(0., 0.));
+        let settings = SettingsInteraction::new()
+            .with_editing_enabled(true)
+            .with_keyboard_navigation_enabled(true);
+
+        let ctx = egui::Context::default();
+        let run = |graph: &mut Graph, events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::new(800., 600.))),
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.add(&mut DefaultGraphView::new(graph).with_interactions(&settings));
+                });
+            });
+        };
+        let delete = egui::Event::Key {
+            key: Key::Delete,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: Modifiers::NONE,
+        };
+
+        run(&mut graph, vec![]);
+        run(&mut graph, vec![]);
+        graph.g[a].set_selected(true);
+
+        // neither hovered nor focused
+        run(&mut graph, vec![egui::Event::PointerGone]);
+        run(&mut graph, vec![delete.clone()]);
+        assert_eq!(graph.node_count(), 1);
+
+        // focus the graph with a click on the empty canvas and move the pointer away
+        let pos = Pos2::new(10., 10.);
+        let button = |pressed| egui::Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        };
+        run(&mut graph, vec![egui::Event::PointerMoved(pos)]);
+        run(&mut graph, vec![button(true)]);
+        run(&mut graph, vec![button(false)]);
+        run(&mut graph, vec![egui::Event::PointerGone]);
+        graph.g[a].set_selected(true);
+        run(&mut graph, vec![]);
+
+        run(&mut graph, vec![delete]);
+        assert_eq!(graph.node_count(), 0);
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_undo_redo_events() {
+        let mut graph: Graph = Graph::new(petgraph::stable_graph::StableGraph::default());
+        graph.set_history_enabled(true);
+        let a = graph.add_node_with_location((), Pos2::new(0., 0.));
+        let b = graph.add_node_with_location((), Pos2::new(100., 0.));
+        graph.set_node_label(a, "renamed".to_string());
+        let settings = SettingsInteraction::new()
+            .with_node_selection_enabled(true)
+            .with_undo_redo_keys_enabled(true);
+
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let ctx = egui::Context::default();
+        let run = |graph: &mut Graph, events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::new(800., 600.))),
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.add(
+                        &mut DefaultGraphView::new(graph)
+                            .with_interactions(&settings)
+                            .with_events(&sender),
+                    );
+                });
+            });
+        };
+        let press = |graph: &mut Graph, modifiers: Modifiers| {
+            let key = egui::Event::Key {
+                key: Key::Z,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers,
+            };
+            run(graph, vec![key]);
+            receiver
+                .try_iter()
+                .filter(|e| !matches!(e, Event::Pan(_) | Event::Zoom(_)))
+                .collect::<Vec<_>>()
+        };
+
+        run(
+            &mut graph,
+            vec![egui::Event::PointerMoved(Pos2::new(10., 10.))],
+        );
+        run(&mut graph, vec![]);
+        receiver.try_iter().for_each(drop);
+
+        let undo = Modifiers::COMMAND | Modifiers::CTRL;
+        assert_eq!(
+            press(&mut graph, undo),
+            vec![Event::NodeLabelChange(PayloadNodeLabelChange {
+                id: a.index(),
+                label: "0".to_string(),
+            })]
+        );
+        assert_eq!(
+            press(&mut graph, undo),
+            vec![Event::NodeRemove(PayloadNodeRemove { id: b.index() })]
+        );
+        assert_eq!(
+            press(&mut graph, undo | Modifiers::SHIFT),
+            vec![Event::NodeCreate(PayloadNodeCreate { id: b.index() })]
+        );
+        assert_eq!(graph.node_count(), 2);
+    }
+
+    #[test]
+    fn test_lasso_and_box_selection() {
+        let mut graph: Graph = Graph::new(petgraph::stable_graph::StableGraph::default());
+        graph.add_node_with_location((), Pos2::new(0., 0.));
+        let settings = SettingsInteraction::new()
+            .with_box_selection_enabled(true)
+            .with_lasso_selection_enabled(true)
+            .with_node_selection_enabled(true);
+
+        let ctx = egui::Context::default();
+        let run = |graph: &mut Graph, events: Vec<egui::Event>, modifiers: Modifiers| {
+            let input = egui::RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::new(800., 600.))),
+                events,
+                modifiers,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.add(&mut DefaultGraphView::new(graph).with_interactions(&settings));
+                });
+            });
+        };
+        // starts a drag on the empty canvas and returns the selection area being drawn
+        let start_drag = |graph: &mut Graph, modifiers: Modifiers| {
+            let (start, end) = (Pos2::new(10., 10.), Pos2::new(40., 40.));
+            let button = |pos, pressed| egui::Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed,
+                modifiers,
+            };
+            run(graph, vec![egui::Event::PointerMoved(start)], modifiers);
+            run(graph, vec![button(start, true)], modifiers);
+            run(graph, vec![egui::Event::PointerMoved(end)], modifiers);
+            let area = Metadata::load_from_ctx(&ctx).selection;
+            run(graph, vec![button(end, false)], modifiers);
+            run(graph, vec![], Modifiers::NONE);
+            area.map(|a| (matches!(a.shape, SelectionShape::Lasso(_)), a.mode))
+        };
+
+        run(&mut graph, vec![], Modifiers::NONE);
+        run(&mut graph, vec![], Modifiers::NONE);
+
+        assert_eq!(
+            start_drag(&mut graph, Modifiers::SHIFT),
+            Some((false, SelectionMode::Replace))
+        );
+        assert_eq!(
+            start_drag(&mut graph, Modifiers::SHIFT | Modifiers::ALT),
+            Some((false, SelectionMode::Remove))
+        );
+        assert_eq!(
+            start_drag(&mut graph, Modifiers::ALT),
+            Some((true, SelectionMode::Replace))
+        );
+        assert_eq!(
+            start_drag(
+                &mut graph,
+                Modifiers::ALT | Modifiers::COMMAND | Modifiers::CTRL
+            ),
+            Some((true, SelectionMode::Add))
+        );
+        assert_eq!(start_drag(&mut graph, Modifiers::NONE), None);
+    }
+}
@@ -0,0 +1,4 @@
+mod raster;
+mod renderer;
+
+pub use renderer::{DefaultRenderer, RenderError, Renderer};
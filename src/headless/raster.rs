@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use egui::{epaint::Mesh, Color32, ImageData, Pos2, Rect, TextureId};
+use tiny_skia::{Pixmap, PremultipliedColorU8};
+
+/// Texture as it was uploaded by egui. Pixels are stored in premultiplied sRGBA.
+struct Texture {
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+}
+
+impl Texture {
+    fn sample(&self, uv: Pos2) -> Color32 {
+        let [w, h] = self.size;
+        if w == 0 || h == 0 {
+            return Color32::WHITE;
+        }
+
+        let x = ((uv.x * w as f32) as usize).min(w - 1);
+        let y = ((uv.y * h as f32) as usize).min(h - 1);
+        self.pixels[y * w + x]
+    }
+}
+
+/// Keeps textures produced by egui during the frame. Only full uploads and patches are applied,
+/// textures are never freed because the renderer lives for one frame.
+#[derive(Default)]
+pub struct Textures {
+    by_id: HashMap<TextureId, Texture>,
+}
+
+impl Textures {
+    pub fn apply(&mut self, id: TextureId, delta: &egui::epaint::ImageDelta) {
+        let (size, pixels): ([usize; 2], Vec<Color32>) = match &delta.image {
+            ImageData::Color(image) => (image.size, image.pixels.clone()),
+            ImageData::Font(image) => (image.size, image.srgba_pixels(None).collect()),
+        };
+
+        let Some([x0, y0]) = delta.pos else {
+            self.by_id.insert(id, Texture { size, pixels });
+            return;
+        };
+
+        let Some(texture) = self.by_id.get_mut(&id) else {
+            return;
+        };
+        for y in 0..size[1] {
+            for x in 0..size[0] {
+                let (tx, ty) = (x0 + x, y0 + y);
+                if tx >= texture.size[0] || ty >= texture.size[1] {
+                    continue;
+                }
+                texture.pixels[ty * texture.size[0] + tx] = pixels[y * size[0] + x];
+            }
+        }
+    }
+
+    fn get(&self, id: TextureId) -> Option<&Texture> {
+        self.by_id.get(&id)
+    }
+}
+
+/// Rasterizes tessellated egui mesh into the pixmap.
+///
+/// * `clip` - clip rect in pixels.
+/// * `pixels_per_point` - scale applied to the mesh vertices which are in points.
+pub fn draw_mesh(
+    target: &mut Pixmap,
+    mesh: &Mesh,
+    clip: Rect,
+    pixels_per_point: f32,
+    textures: &Textures,
+) {
+    let texture = textures.get(mesh.texture_id);
+    let clip = clip.intersect(Rect::from_min_max(
+        Pos2::ZERO,
+        Pos2::new(target.width() as f32, target.height() as f32),
+    ));
+    if !clip.is_positive() {
+        return;
+    }
+
+    for triangle in mesh.indices.chunks_exact(3) {
+        let vertices = [
+            mesh.vertices[triangle[0] as usize],
+            mesh.vertices[triangle[1] as usize],
+            mesh.vertices[triangle[2] as usize],
+        ];
+        let positions = vertices.map(|v| (v.pos.to_vec2() * pixels_per_point).to_pos2());
+        draw_triangle(
+            target,
+            clip,
+            positions,
+            vertices.map(|v| v.uv),
+            vertices.map(|v| v.color),
+            texture,
+        );
+    }
+}
+
+fn draw_triangle(
+    target: &mut Pixmap,
+    clip: Rect,
+    [a, b, c]: [Pos2; 3],
+    uvs: [Pos2; 3],
+    colors: [Color32; 3],
+    texture: Option<&Texture>,
+) {
+    let area = edge(a, b, c);
+    if area == 0. {
+        return;
+    }
+
+    let bounds = Rect::from_points(&[a, b, c]).intersect(clip);
+    if !bounds.is_positive() {
+        return;
+    }
+
+    let width = target.width() as usize;
+    let pixels = target.pixels_mut();
+    for y in bounds.min.y.floor() as usize..bounds.max.y.ceil() as usize {
+        for x in bounds.min.x.floor() as usize..bounds.max.x.ceil() as usize {
+            let center = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            if !clip.contains(center) {
+                continue;
+            }
+
+            // barycentric coordinates normalized so that they are positive inside the triangle
+            // independently of the winding order
+            let weights = [
+                edge(b, c, center) / area,
+                edge(c, a, center) / area,
+                edge(a, b, center) / area,
+            ];
+            if weights.iter().any(|w| *w < 0.) {
+                continue;
+            }
+
+            let mut src = interpolate_color(colors, weights);
+            if let Some(texture) = texture {
+                let uv = uvs
+                    .iter()
+                    .zip(weights)
+                    .fold(Pos2::ZERO, |acc, (uv, w)| acc + uv.to_vec2() * w);
+                src = multiply(src, texture.sample(uv));
+            }
+
+            let dst = &mut pixels[y * width + x];
+            *dst = blend(src, *dst);
+        }
+    }
+}
+
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+fn interpolate_color(colors: [Color32; 3], w: [f32; 3]) -> [f32; 4] {
+    let mut res = [0.; 4];
+    for (c, w) in colors.iter().zip(w) {
+        for (channel, value) in res.iter_mut().zip(c.to_array()) {
+            *channel += f32::from(value) * w;
+        }
+    }
+    res
+}
+
+fn multiply(color: [f32; 4], by: Color32) -> [f32; 4] {
+    let mut res = color;
+    for (channel, value) in res.iter_mut().zip(by.to_array()) {
+        *channel *= f32::from(value) / 255.;
+    }
+    res
+}
+
+/// Blends premultiplied `src` over premultiplied `dst`.
+fn blend(src: [f32; 4], dst: PremultipliedColorU8) -> PremultipliedColorU8 {
+    let inv_alpha = 1. - src[3].clamp(0., 255.) / 255.;
+    let channel = |s: f32, d: u8| (s + f32::from(d) * inv_alpha).round().clamp(0., 255.) as u8;
+
+    let a = channel(src[3], dst.alpha());
+    let r = channel(src[0], dst.red()).min(a);
+    let g = channel(src[1], dst.green()).min(a);
+    let b = channel(src[2], dst.blue()).min(a);
+
+    PremultipliedColorU8::from_rgba(r, g, b, a).unwrap_or(dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::epaint::Vertex;
+
+    #[test]
+    fn test_draw_mesh_fills_triangle() {
+        let mut pixmap = Pixmap::new(10, 10).unwrap();
+        let mut mesh = Mesh::default();
+        mesh.colored_vertex(Pos2::new(0., 0.), Color32::RED);
+        mesh.colored_vertex(Pos2::new(10., 0.), Color32::RED);
+        mesh.colored_vertex(Pos2::new(0., 10.), Color32::RED);
+        mesh.add_triangle(0, 1, 2);
+
+        draw_mesh(
+            &mut pixmap,
+            &mesh,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(10., 10.)),
+            1.,
+            &Textures::default(),
+        );
+
+        let inside = pixmap.pixel(1, 1).unwrap();
+        assert_eq!((inside.red(), inside.alpha()), (255, 255));
+        let outside = pixmap.pixel(9, 9).unwrap();
+        assert_eq!(outside.alpha(), 0);
+    }
+
+    #[test]
+    fn test_draw_mesh_respects_clip() {
+        let mut pixmap = Pixmap::new(10, 10).unwrap();
+        let mut mesh = Mesh::default();
+        mesh.vertices.extend([
+            Vertex {
+                pos: Pos2::new(0., 0.),
+                uv: Pos2::ZERO,
+                color: Color32::WHITE,
+            },
+            Vertex {
+                pos: Pos2::new(20., 0.),
+                uv: Pos2::ZERO,
+                color: Color32::WHITE,
+            },
+            Vertex {
+                pos: Pos2::new(0., 20.),
+                uv: Pos2::ZERO,
+                color: Color32::WHITE,
+            },
+        ]);
+        mesh.add_triangle(0, 1, 2);
+
+        draw_mesh(
+            &mut pixmap,
+            &mesh,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(5., 5.)),
+            1.,
+            &Textures::default(),
+        );
+
+        assert_eq!(pixmap.pixel(2, 2).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(6, 2).unwrap().alpha(), 0);
+    }
+}
@@ -0,0 +1,331 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use egui::{
+    epaint::Primitive, Color32, Context, LayerId, Pos2, RawInput, Rect, Vec2, ViewportId, Visuals,
+};
+use petgraph::{graph::IndexType, stable_graph::DefaultIx, Directed, EdgeType};
+use tiny_skia::Pixmap;
+
+use crate::{
+    draw::{DrawContext, Drawer},
+    layouts::{self, Layout, LayoutState},
+    settings::SettingsStyle,
+    DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, Graph, Metadata,
+};
+
+use super::raster::{draw_mesh, Textures};
+
+pub type DefaultRenderer<'a> = Renderer<
+    'a,
+    (),
+    (),
+    Directed,
+    DefaultIx,
+    DefaultNodeShape,
+    DefaultEdgeShape,
+    layouts::random::State,
+    layouts::random::Random,
+>;
+
+/// Error which can occur while rendering the graph without a window.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderError {
+    /// Requested image has zero width or height.
+    InvalidSize { width: u32, height: u32 },
+    /// Failed to encode the image to PNG.
+    Encoding(String),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSize { width, height } => {
+                write!(f, "invalid image size {width}x{height}")
+            }
+            Self::Encoding(err) => write!(f, "failed to encode png: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Renders [`Graph`] into an image without a GPU or a window.
+///
+/// The renderer runs a single egui frame on a fresh [`egui::Context`], draws the graph with the same
+/// [`DisplayNode`] and [`DisplayEdge`] implementations which are used by [`crate::GraphView`],
+/// tessellates the resulting shapes and rasterizes them in software.
+///
+/// Layout is applied once with the default state before drawing, the graph is fitted into the image.
+///
+/// ```
+/// use egui_graphs::{headless::DefaultRenderer, random_graph};
+///
+/// let mut g = random_graph(10, 15);
+/// let png = DefaultRenderer::new(&mut g).with_size(200, 100).render_png().unwrap();
+/// assert!(!png.is_empty());
+/// ```
+pub struct Renderer<
+    'a,
+    N = (),
+    E = (),
+    Ty = Directed,
+    Ix = DefaultIx,
+    Nd = DefaultNodeShape,
+    Ed = DefaultEdgeShape,
+    S = layouts::random::State,
+    L = layouts::random::Random,
+> where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Nd: DisplayNode<N, E, Ty, Ix>,
+    Ed: DisplayEdge<N, E, Ty, Ix, Nd>,
+    S: LayoutState,
+    L: Layout<S>,
+{
+    g: &'a mut Graph<N, E, Ty, Ix, Nd, Ed>,
+
+    settings_style: SettingsStyle,
+    visuals: Visuals,
+    background: Option<Color32>,
+
+    width: u32,
+    height: u32,
+    pixels_per_point: f32,
+    screen_padding: f32,
+
+    _marker: PhantomData<(Nd, Ed, L, S)>,
+}
+
+impl<'a, N, E, Ty, Ix, Nd, Ed, S, L> Renderer<'a, N, E, Ty, Ix, Nd, Ed, S, L>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Nd: DisplayNode<N, E, Ty, Ix>,
+    Ed: DisplayEdge<N, E, Ty, Ix, Nd>,
+    S: LayoutState,
+    L: Layout<S>,
+{
+    /// Creates a new renderer producing 800x600 images with default egui visuals.
+    pub fn new(g: &'a mut Graph<N, E, Ty, Ix, Nd, Ed>) -> Self {
+        Self {
+            g,
+
+            settings_style: SettingsStyle::default(),
+            visuals: Visuals::default(),
+            background: Option::default(),
+
+            width: 800,
+            height: 600,
+            pixels_per_point: 1.,
+            screen_padding: 0.3,
+
+            _marker: PhantomData,
+        }
+    }
+
+    /// Modifies default style settings.
+    pub fn with_styles(mut self, settings_style: &SettingsStyle) -> Self {
+        self.settings_style = settings_style.clone();
+        self
+    }
+
+    /// Sets egui visuals used by the displays, e.g. [`Visuals::light`].
+    pub fn with_visuals(mut self, visuals: Visuals) -> Self {
+        self.visuals = visuals;
+        self
+    }
+
+    /// Sets background color of the image.
+    ///
+    /// Default: panel fill color of the visuals
+    pub fn with_background(mut self, color: Color32) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Sets size of the resulting image in pixels.
+    ///
+    /// Default: `800x600`
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Scale factor between egui points and image pixels.
+    ///
+    /// Default: `1.0`
+    pub fn with_pixels_per_point(mut self, pixels_per_point: f32) -> Self {
+        self.pixels_per_point = pixels_per_point;
+        self
+    }
+
+    /// Padding around the graph when fitting it into the image.
+    ///
+    /// Default: `0.3`
+    pub fn with_screen_padding(mut self, padding: f32) -> Self {
+        self.screen_padding = padding;
+        self
+    }
+
+    /// Renders the graph and encodes the result as PNG.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RenderError`] if the image size is invalid or the image could not be encoded.
+    pub fn render_png(&mut self) -> Result<Vec<u8>, RenderError> {
+        self.render()?
+            .encode_png()
+            .map_err(|err| RenderError::Encoding(err.to_string()))
+    }
+
+    /// Renders the graph into a [`Pixmap`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RenderError::InvalidSize`] if the image has zero width or height.
+    pub fn render(&mut self) -> Result<Pixmap, RenderError> {
+        let Some(mut pixmap) = Pixmap::new(self.width, self.height) else {
+            return Err(RenderError::InvalidSize {
+                width: self.width,
+                height: self.height,
+            });
+        };
+
+        let ctx = Context::default();
+        ctx.set_visuals(self.visuals.clone());
+
+        let background = self.background.unwrap_or(self.visuals.panel_fill);
+        let [r, g, b, a] = background.to_srgba_unmultiplied();
+        pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, a));
+
+        let rect = Rect::from_min_size(
+            Pos2::ZERO,
+            Vec2::new(self.width as f32, self.height as f32) / self.pixels_per_point,
+        );
+        let mut input = RawInput {
+            screen_rect: Some(rect),
+            ..Default::default()
+        };
+        input
+            .viewports
+            .entry(ViewportId::ROOT)
+            .or_default()
+            .native_pixels_per_point = Some(self.pixels_per_point);
+
+        let mut layout = L::from_state(S::default());
+        layout.next(self.g);
+
+        let meta = self.fit(rect);
+        let is_directed = self.g.is_directed();
+        let output = ctx.run(input, |ctx| {
+            let painter = ctx.layer_painter(LayerId::background());
+            Drawer::<N, E, Ty, Ix, Nd, Ed, S, L>::new(
+                self.g,
+                &DrawContext {
+                    ctx,
+                    painter: &painter,
+                    meta: &meta,
+                    is_directed,
+                    style: &self.settings_style,
+                },
+            )
+            .draw();
+        });
+
+        let mut textures = Textures::default();
+        for (id, delta) in &output.textures_delta.set {
+            textures.apply(*id, delta);
+        }
+
+        let pixels_per_point = output.pixels_per_point;
+        for clipped in ctx.tessellate(output.shapes, pixels_per_point) {
+            let Primitive::Mesh(mesh) = clipped.primitive else {
+                continue;
+            };
+
+            let clip = Rect::from_min_max(
+                (clipped.clip_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+                (clipped.clip_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+            );
+            draw_mesh(&mut pixmap, &mesh, clip, pixels_per_point, &textures);
+        }
+
+        Ok(pixmap)
+    }
+
+    /// Computes zoom and pan to fit the graph into the provided rect.
+    fn fit(&self, rect: Rect) -> Metadata {
+        let mut meta = Metadata::default();
+        meta.first_frame = false;
+        if self.g.node_count() == 0 {
+            return meta;
+        }
+
+        self.g
+            .nodes_iter()
+            .for_each(|(_, n)| meta.comp_iter_bounds(n));
+
+        let bounds = meta.graph_bounds();
+        let mut diag = bounds.max - bounds.min;
+
+        // if the graph consists from one node, use a default size
+        if diag == Vec2::ZERO {
+            diag = Vec2::new(1., 100.);
+        }
+
+        let graph_size = diag * (1. + self.screen_padding);
+        let zoom = (rect.width() / graph_size.x).min(rect.height() / graph_size.y);
+
+        meta.zoom = zoom;
+        meta.pan = rect.center().to_vec2() - bounds.center().to_vec2() * zoom;
+
+        meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+    use crate::to_graph;
+
+    #[test]
+    fn test_render_draws_graph() {
+        let mut sg: StableGraph<(), ()> = StableGraph::new();
+        let a = sg.add_node(());
+        let b = sg.add_node(());
+        sg.add_edge(a, b, ());
+        let mut g = to_graph(&sg);
+
+        let pixmap = DefaultRenderer::new(&mut g)
+            .with_size(64, 32)
+            .with_background(Color32::BLACK)
+            .render()
+            .unwrap();
+
+        assert_eq!((pixmap.width(), pixmap.height()), (64, 32));
+        assert!(pixmap.pixels().iter().any(|p| p.red() > 0));
+    }
+
+    #[test]
+    fn test_render_invalid_size() {
+        let mut g = Graph::from(&StableGraph::<(), ()>::new());
+
+        let res = DefaultRenderer::new(&mut g).with_size(0, 10).render();
+
+        assert_eq!(
+            res.unwrap_err(),
+            RenderError::InvalidSize {
+                width: 0,
+                height: 10
+            }
+        );
+    }
+}
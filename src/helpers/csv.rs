@@ -0,0 +1,704 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Lines};
+
+use egui::{Color32, Pos2};
+use petgraph::{
+    graph::IndexType,
+    stable_graph::{NodeIndex, StableGraph},
+    EdgeType,
+};
+
+use crate::{DisplayEdge, DisplayNode, Graph};
+
+use super::ImportedGraph;
+
+use super::{
+    add_node_imported,
+    parse::{check_count, parse_token, ParseError},
+};
+
+/// Column of a CSV file referenced by its header name or by its zero-based position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+    Name(String),
+    Index(usize),
+}
+
+impl From<usize> for CsvColumn {
+    fn from(idx: usize) -> Self {
+        Self::Index(idx)
+    }
+}
+
+impl From<&str> for CsvColumn {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+/// Describes the layout of an edge-list CSV file for [`edge_list_from_csv`].
+#[derive(Debug, Clone)]
+pub struct CsvEdgeListOptions {
+    pub(crate) delimiter: char,
+    pub(crate) has_header: bool,
+    pub(crate) source: CsvColumn,
+    pub(crate) target: CsvColumn,
+    pub(crate) weight: Option<CsvColumn>,
+    pub(crate) label: Option<CsvColumn>,
+}
+
+impl Default for CsvEdgeListOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            has_header: true,
+            source: CsvColumn::Index(0),
+            target: CsvColumn::Index(1),
+            weight: Option::default(),
+            label: Option::default(),
+        }
+    }
+}
+
+impl CsvEdgeListOptions {
+    /// Creates new [`CsvEdgeListOptions`] with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default: `,`
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether the first record is a header. Columns can be referenced by name only when it is present.
+    ///
+    /// Default: `true`
+    pub fn with_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Column with the id of the edge source node.
+    ///
+    /// Default: first column
+    pub fn with_source(mut self, column: impl Into<CsvColumn>) -> Self {
+        self.source = column.into();
+        self
+    }
+
+    /// Column with the id of the edge target node.
+    ///
+    /// Default: second column
+    pub fn with_target(mut self, column: impl Into<CsvColumn>) -> Self {
+        self.target = column.into();
+        self
+    }
+
+    /// Column with the edge weight. Empty cells produce edges without weight.
+    ///
+    /// Default: `None`
+    pub fn with_weight(mut self, column: impl Into<CsvColumn>) -> Self {
+        self.weight = Some(column.into());
+        self
+    }
+
+    /// Column with the edge label. Edges get default labels when not set.
+    ///
+    /// Default: `None`
+    pub fn with_label(mut self, column: impl Into<CsvColumn>) -> Self {
+        self.label = Some(column.into());
+        self
+    }
+}
+
+/// Describes the layout of a node attributes CSV file for [`node_attributes_from_csv`].
+#[derive(Debug, Clone)]
+pub struct CsvNodeAttributesOptions {
+    pub(crate) delimiter: char,
+    pub(crate) has_header: bool,
+    pub(crate) id: CsvColumn,
+    pub(crate) label: Option<CsvColumn>,
+    pub(crate) location: Option<(CsvColumn, CsvColumn)>,
+    pub(crate) color: Option<CsvColumn>,
+}
+
+impl Default for CsvNodeAttributesOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            has_header: true,
+            id: CsvColumn::Index(0),
+            label: Option::default(),
+            location: Option::default(),
+            color: Option::default(),
+        }
+    }
+}
+
+impl CsvNodeAttributesOptions {
+    /// Creates new [`CsvNodeAttributesOptions`] with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default: `,`
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether the first record is a header. Columns can be referenced by name only when it is present.
+    ///
+    /// Default: `true`
+    pub fn with_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Column with the node id. Ids are matched against node payloads.
+    ///
+    /// Default: first column
+    pub fn with_id(mut self, column: impl Into<CsvColumn>) -> Self {
+        self.id = column.into();
+        self
+    }
+
+    /// Column with the node label.
+    ///
+    /// Default: `None`
+    pub fn with_label(mut self, column: impl Into<CsvColumn>) -> Self {
+        self.label = Some(column.into());
+        self
+    }
+
+    /// Columns with x and y coordinates of the node.
+    ///
+    /// Default: `None`
+    pub fn with_location(mut self, x: impl Into<CsvColumn>, y: impl Into<CsvColumn>) -> Self {
+        self.location = Some((x.into(), y.into()));
+        self
+    }
+
+    /// Column with the node color in `#RRGGBB` or `#RRGGBBAA` format.
+    ///
+    /// Default: `None`
+    pub fn with_color(mut self, column: impl Into<CsvColumn>) -> Self {
+        self.color = Some(column.into());
+        self
+    }
+}
+
+/// Reads the edge list from CSV and builds a [`Graph`] from it.
+///
+/// Nodes are created for every distinct id found in source and target columns, the id is used as
+/// the node payload and label. Edge payload is the optional weight.
+///
+/// Fields can be quoted with `"`, quoted fields can not span multiple lines. Empty lines are skipped.
+///
+/// ```
+/// use egui_graphs::{edge_list_from_csv, CsvEdgeListOptions, Graph};
+///
+/// let csv = "from,to,cost\na,b,1.5\nb,c,\n";
+/// let options = CsvEdgeListOptions::new()
+///     .with_source("from")
+///     .with_target("to")
+///     .with_weight("cost");
+/// let g: Graph<String, Option<f64>> = edge_list_from_csv(csv.as_bytes(), &options).unwrap();
+///
+/// assert_eq!(g.node_count(), 3);
+/// assert_eq!(g.edge_count(), 2);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParseError`] with the line and column of the malformed field.
+pub fn edge_list_from_csv<Ty, Ix, Dn, De>(
+    reader: impl BufRead,
+    options: &CsvEdgeListOptions,
+) -> Result<ImportedGraph<Ty, Ix, Dn, De>, ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let mut g = Graph::new(StableGraph::default());
+    let mut idx_by_id = HashMap::new();
+
+    let mut records = Records::new(reader, options.delimiter, options.has_header)?;
+    let source = records.resolve(&options.source)?;
+    let target = records.resolve(&options.target)?;
+    let weight = records.resolve_optional(options.weight.as_ref())?;
+    let label = records.resolve_optional(options.label.as_ref())?;
+
+    while let Some(record) = records.next_record()? {
+        let start_id = record.get(source)?.value.trim().to_string();
+        let end_id = record.get(target)?.value.trim().to_string();
+
+        let weight = match weight {
+            Some(col) => {
+                let field = record.get(col)?;
+                match field.value.trim() {
+                    "" => None,
+                    value => Some(parse_token(record.line, field.column, value, "weight")?),
+                }
+            }
+            None => None,
+        };
+
+        let source_column = record.get(source)?.column;
+        let start = node_by_id(&mut g, &mut idx_by_id, start_id, record.line, source_column)?;
+        let end = node_by_id(
+            &mut g,
+            &mut idx_by_id,
+            end_id,
+            record.line,
+            record.get(target)?.column,
+        )?;
+        check_count::<Ix>(
+            record.line,
+            source_column,
+            g.edge_count().checked_add(1),
+            "edges",
+        )?;
+        match label {
+            Some(col) => {
+                let label = record.get(col)?.value.clone();
+                g.add_edge_with_label(start, end, weight, label);
+            }
+            None => {
+                g.add_edge(start, end, weight);
+            }
+        }
+    }
+
+    Ok(g)
+}
+
+/// Reads node attributes from CSV and applies them to the nodes of the graph.
+///
+/// Nodes are matched by their payload, nodes which are not yet in the graph are added to it.
+/// The graph is left partially updated if an error occurs.
+///
+/// # Errors
+///
+/// Returns [`ParseError`] with the line and column of the malformed field.
+pub fn node_attributes_from_csv<E, Ty, Ix, Dn, De>(
+    g: &mut Graph<String, E, Ty, Ix, Dn, De>,
+    reader: impl BufRead,
+    options: &CsvNodeAttributesOptions,
+) -> Result<(), ParseError>
+where
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, E, Ty, Ix>,
+    De: DisplayEdge<String, E, Ty, Ix, Dn>,
+{
+    let mut idx_by_id = g
+        .nodes_iter()
+        .map(|(idx, n)| (n.payload().clone(), idx))
+        .collect::<HashMap<_, _>>();
+
+    let mut records = Records::new(reader, options.delimiter, options.has_header)?;
+    let id = records.resolve(&options.id)?;
+    let label = records.resolve_optional(options.label.as_ref())?;
+    let color = records.resolve_optional(options.color.as_ref())?;
+    let location = match &options.location {
+        Some((x, y)) => Some((records.resolve(x)?, records.resolve(y)?)),
+        None => None,
+    };
+
+    while let Some(record) = records.next_record()? {
+        let node_id = record.get(id)?.value.trim().to_string();
+
+        let label = match label {
+            Some(col) => Some(record.get(col)?.value.clone()),
+            None => None,
+        };
+
+        let location = match location {
+            Some((x_col, y_col)) => {
+                let (x, y) = (record.get(x_col)?, record.get(y_col)?);
+                Some(Pos2::new(
+                    parse_token(record.line, x.column, &x.value, "coordinate")?,
+                    parse_token(record.line, y.column, &y.value, "coordinate")?,
+                ))
+            }
+            None => None,
+        };
+
+        let color = match color {
+            Some(col) => {
+                let field = record.get(col)?;
+                let Some(c) = parse_color(field.value.trim()) else {
+                    return Err(ParseError::new(
+                        record.line,
+                        field.column,
+                        format!("invalid color `{}`", field.value),
+                    ));
+                };
+                Some(c)
+            }
+            None => None,
+        };
+
+        let idx = node_by_id(
+            g,
+            &mut idx_by_id,
+            node_id,
+            record.line,
+            record.get(id)?.column,
+        )?;
+        let Some(n) = g.node_mut(idx) else {
+            continue;
+        };
+        if let Some(label) = label {
+            n.set_label(label);
+        }
+        if let Some(loc) = location {
+            n.set_location(loc);
+        }
+        if let Some(c) = color {
+            n.set_color(c);
+        }
+    }
+
+    Ok(())
+}
+
+fn node_by_id<E, Ty, Ix, Dn, De>(
+    g: &mut Graph<String, E, Ty, Ix, Dn, De>,
+    idx_by_id: &mut HashMap<String, NodeIndex<Ix>>,
+    id: String,
+    line: usize,
+    column: usize,
+) -> Result<NodeIndex<Ix>, ParseError>
+where
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, E, Ty, Ix>,
+    De: DisplayEdge<String, E, Ty, Ix, Dn>,
+{
+    if let Some(idx) = idx_by_id.get(&id) {
+        return Ok(*idx);
+    }
+
+    check_count::<Ix>(line, column, g.node_count().checked_add(1), "nodes")?;
+    let idx = add_node_imported(g, id.clone(), id.clone(), None);
+    idx_by_id.insert(id, idx);
+    Ok(idx)
+}
+
+/// Parses colors in `#RRGGBB` and `#RRGGBBAA` formats.
+fn parse_color(text: &str) -> Option<Color32> {
+    let hex = text.strip_prefix('#')?;
+    if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+    let a = if hex.len() == 8 { channel(6)? } else { 255 };
+
+    Some(Color32::from_rgba_unmultiplied(r, g, b, a))
+}
+
+struct Field {
+    column: usize,
+    value: String,
+}
+
+struct Record {
+    line: usize,
+    /// Column right after the last character of the line, used to report missing fields.
+    end_column: usize,
+    fields: Vec<Field>,
+}
+
+impl Record {
+    fn get(&self, idx: usize) -> Result<&Field, ParseError> {
+        self.fields.get(idx).ok_or_else(|| {
+            ParseError::new(
+                self.line,
+                self.end_column,
+                format!(
+                    "expected at least {} fields, found {}",
+                    idx + 1,
+                    self.fields.len()
+                ),
+            )
+        })
+    }
+}
+
+struct Records<R: BufRead> {
+    lines: Lines<R>,
+    line: usize,
+    delimiter: char,
+    header: Option<Record>,
+}
+
+impl<R: BufRead> Records<R> {
+    fn new(reader: R, delimiter: char, has_header: bool) -> Result<Self, ParseError> {
+        let mut records = Self {
+            lines: reader.lines(),
+            line: 0,
+            delimiter,
+            header: None,
+        };
+        if has_header {
+            records.header = records.next_record()?;
+        }
+
+        Ok(records)
+    }
+
+    fn next_record(&mut self) -> Result<Option<Record>, ParseError> {
+        for text in self.lines.by_ref() {
+            self.line += 1;
+            let line = self.line;
+            let text = text.map_err(|err| ParseError::new(line, 1, err.to_string()))?;
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            return Ok(Some(Record {
+                line,
+                end_column: text.chars().count() + 1,
+                fields: split_record(&text, line, self.delimiter)?,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Resolves column to its zero-based position.
+    fn resolve(&self, column: &CsvColumn) -> Result<usize, ParseError> {
+        let name = match column {
+            CsvColumn::Index(idx) => return Ok(*idx),
+            CsvColumn::Name(name) => name,
+        };
+
+        let Some(header) = &self.header else {
+            return Err(ParseError::new(
+                1,
+                1,
+                format!("column `{name}` is referenced by name but the file has no header"),
+            ));
+        };
+
+        header
+            .fields
+            .iter()
+            .position(|f| f.value.trim() == name)
+            .ok_or_else(|| {
+                ParseError::new(
+                    header.line,
+                    1,
+                    format!("column `{name}` is not found in the header"),
+                )
+            })
+    }
+
+    fn resolve_optional(&self, column: Option<&CsvColumn>) -> Result<Option<usize>, ParseError> {
+        column.map(|c| self.resolve(c)).transpose()
+    }
+}
+
+/// Splits the line into fields. Supports quoted fields with `""` as an escaped quote.
+fn split_record(line: &str, line_no: usize, delimiter: char) -> Result<Vec<Field>, ParseError> {
+    let mut fields = vec![];
+    let mut chars = line.chars().zip(1..).peekable();
+
+    let mut value = String::new();
+    let mut start = 1;
+    let mut quote_start = None;
+    let mut after_quote = false;
+    while let Some((c, column)) = chars.next() {
+        if quote_start.is_some() {
+            if c != '"' {
+                value.push(c);
+            } else if chars.peek().is_some_and(|(next, _)| *next == '"') {
+                chars.next();
+                value.push('"');
+            } else {
+                quote_start = None;
+                after_quote = true;
+            }
+            continue;
+        }
+
+        if c == delimiter {
+            fields.push(Field {
+                column: start,
+                value: std::mem::take(&mut value),
+            });
+            start = column + 1;
+            after_quote = false;
+            continue;
+        }
+
+        if after_quote {
+            if c.is_whitespace() {
+                continue;
+            }
+            return Err(ParseError::new(
+                line_no,
+                column,
+                "unexpected character after the closing quote",
+            ));
+        }
+
+        if c == '"' && value.trim().is_empty() {
+            value.clear();
+            quote_start = Some(column);
+            continue;
+        }
+
+        value.push(c);
+    }
+
+    if let Some(column) = quote_start {
+        return Err(ParseError::new(
+            line_no,
+            column,
+            "unterminated quoted field",
+        ));
+    }
+
+    fields.push(Field {
+        column: start,
+        value,
+    });
+
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_record_quoted() {
+        let fields = split_record(r#"a,"b, ""c""", d"#, 1, ',').unwrap();
+        let values = fields.iter().map(|f| f.value.as_str()).collect::<Vec<_>>();
+        let columns = fields.iter().map(|f| f.column).collect::<Vec<_>>();
+
+        assert_eq!(values, vec!["a", r#"b, "c""#, " d"]);
+        assert_eq!(columns, vec![1, 3, 14]);
+    }
+
+    #[test]
+    fn test_split_record_unterminated_quote() {
+        let err = split_record(r#"a,"b"#, 3, ',').err().unwrap();
+        assert_eq!((err.line, err.column), (3, 3));
+    }
+
+    #[test]
+    fn test_edge_list_from_csv_by_index() {
+        let csv = "a;b;x\nb;c;y\n\na;a;z\n";
+        let options = CsvEdgeListOptions::new()
+            .with_header(false)
+            .with_delimiter(';')
+            .with_label(2);
+
+        let g: Graph<String, Option<f64>> = edge_list_from_csv(csv.as_bytes(), &options).unwrap();
+
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(g.edge_count(), 3);
+        let labels = g.edges_iter().map(|(_, e)| e.label()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["x", "y", "z"]);
+    }
+
+    #[test]
+    fn test_edge_list_from_csv_invalid_weight() {
+        let csv = "source,target,weight\na,b,1\nb,c,heavy\n";
+        let options = CsvEdgeListOptions::new().with_weight("weight");
+
+        let err = edge_list_from_csv::<
+            petgraph::Directed,
+            u32,
+            crate::DefaultNodeShape,
+            crate::DefaultEdgeShape,
+        >(csv.as_bytes(), &options)
+        .err()
+        .unwrap();
+
+        assert_eq!((err.line, err.column), (3, 5));
+    }
+
+    #[test]
+    fn test_edge_list_from_csv_unknown_column() {
+        let csv = "source,target\na,b\n";
+        let options = CsvEdgeListOptions::new().with_weight("weight");
+
+        let res = edge_list_from_csv::<
+            petgraph::Directed,
+            u32,
+            crate::DefaultNodeShape,
+            crate::DefaultEdgeShape,
+        >(csv.as_bytes(), &options);
+
+        assert_eq!(res.err().unwrap().line, 1);
+    }
+
+    #[test]
+    fn test_edge_list_from_csv_too_many_elements() {
+        let options = CsvEdgeListOptions::new().with_header(false);
+        let import = |csv: String| {
+            edge_list_from_csv::<
+                petgraph::Directed,
+                u8,
+                crate::DefaultNodeShape,
+                crate::DefaultEdgeShape,
+            >(csv.as_bytes(), &options)
+            .err()
+            .unwrap()
+        };
+
+        let err = import(
+            (0..300)
+                .map(|i| format!("a,{i}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        assert_eq!((err.line, err.column), (255, 3));
+
+        let err = import("a,b\n".repeat(300));
+        assert_eq!((err.line, err.column), (256, 1));
+    }
+
+    #[test]
+    fn test_node_attributes_from_csv() {
+        let mut g: Graph<String, Option<f64>> = edge_list_from_csv(
+            "a,b\n".as_bytes(),
+            &CsvEdgeListOptions::new().with_header(false),
+        )
+        .unwrap();
+        let csv = "id,name,x,y,color\nb,Bee,1,2,#ff0000\nc,Sea,3,4,#00ff0080\n";
+        let options = CsvNodeAttributesOptions::new()
+            .with_label("name")
+            .with_location("x", "y")
+            .with_color("color");
+
+        node_attributes_from_csv(&mut g, csv.as_bytes(), &options).unwrap();
+
+        assert_eq!(g.node_count(), 3);
+        let b = g.nodes_iter().find(|(_, n)| n.payload() == "b").unwrap().1;
+        assert_eq!(b.label(), "Bee");
+        assert_eq!(b.location(), Pos2::new(1., 2.));
+        assert_eq!(b.color(), Some(Color32::RED));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#000000"), Some(Color32::BLACK));
+        assert_eq!(parse_color("000000"), None);
+        assert_eq!(parse_color("#00000"), None);
+        assert_eq!(parse_color("#zz0000"), None);
+    }
+}
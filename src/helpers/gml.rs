@@ -0,0 +1,525 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, Lines, Write};
+
+use egui::Pos2;
+use petgraph::{
+    graph::IndexType,
+    stable_graph::{NodeIndex, StableGraph},
+    EdgeType,
+};
+
+use crate::{DisplayEdge, DisplayNode, Edge, Graph};
+
+use super::{
+    add_node_imported,
+    parse::{check_count, escape_quotes, parse_token, quoted_tokens, unescape_quotes, ParseError},
+    ImportedGraph,
+};
+
+/// Reads a graph in GML format and builds a [`Graph`] from it.
+///
+/// The input is read in a streaming manner: every `node` and `edge` list is added to the graph as
+/// soon as it is parsed. Nodes get their `id` as payload and `label` as label, coordinates are taken
+/// from `graphics [ x .. y .. ]` when present. Edges get `weight`, or `value` when weight is missing,
+/// as payload and `label` as label. Other keys are skipped. Direction of the graph is defined by `Ty`,
+/// the `directed` key is not taken into account.
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if the input is malformed, a node has no `id`, an edge references
+/// a node which was not declared before it or the nodes or edges do not fit into the index type
+/// of the graph.
+pub fn graph_from_gml<Ty, Ix, Dn, De>(
+    reader: impl BufRead,
+) -> Result<ImportedGraph<Ty, Ix, Dn, De>, ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let mut tokens = Tokens::new(reader);
+    let mut g = Graph::new(StableGraph::default());
+    let mut has_graph = false;
+
+    while let Some(token) = tokens.next()? {
+        let key = token.key()?;
+        if key != "graph" {
+            read_value(&mut tokens, &token)?;
+            continue;
+        }
+
+        tokens.expect_open(&token)?;
+        read_graph(&mut tokens, &mut g)?;
+        has_graph = true;
+    }
+
+    if !has_graph {
+        return Err(ParseError::new(
+            tokens.line.max(1),
+            1,
+            "expected `graph` list",
+        ));
+    }
+
+    Ok(g)
+}
+
+/// Writes the [`Graph`] in GML format.
+///
+/// Nodes get their indices as ids and are written with labels and locations. Edges are written
+/// with labels and without weights, use [`write_gml_custom`] to write them.
+///
+/// # Errors
+///
+/// Returns error of the writer.
+pub fn write_gml<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+    writer: impl Write,
+) -> io::Result<()>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    write_gml_custom(g, writer, |_| None)
+}
+
+/// Writes the [`Graph`] in GML format using custom function to get edge weights.
+///
+/// # Errors
+///
+/// Returns error of the writer.
+pub fn write_gml_custom<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+    mut writer: impl Write,
+    edge_weight: impl Fn(&Edge<N, E, Ty, Ix, Dn, De>) -> Option<f64>,
+) -> io::Result<()>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    writeln!(writer, "graph [")?;
+    writeln!(writer, "  directed {}", u8::from(g.is_directed()))?;
+
+    for (idx, n) in g.nodes_iter() {
+        let loc = n.location();
+        writeln!(writer, "  node [")?;
+        writeln!(writer, "    id {}", idx.index())?;
+        writeln!(writer, "    label \"{}\"", escape_quotes(&n.label()))?;
+        writeln!(writer, "    graphics [")?;
+        writeln!(writer, "      x {}", loc.x)?;
+        writeln!(writer, "      y {}", loc.y)?;
+        writeln!(writer, "    ]")?;
+        writeln!(writer, "  ]")?;
+    }
+
+    for (idx, e) in g.edges_iter() {
+        let Some((start, end)) = g.edge_endpoints(idx) else {
+            continue;
+        };
+        writeln!(writer, "  edge [")?;
+        writeln!(writer, "    source {}", start.index())?;
+        writeln!(writer, "    target {}", end.index())?;
+        writeln!(writer, "    label \"{}\"", escape_quotes(&e.label()))?;
+        if let Some(weight) = edge_weight(e) {
+            writeln!(writer, "    weight {weight}")?;
+        }
+        writeln!(writer, "  ]")?;
+    }
+
+    writeln!(writer, "]")
+}
+
+fn read_graph<Ty, Ix, Dn, De>(
+    tokens: &mut Tokens<impl BufRead>,
+    g: &mut ImportedGraph<Ty, Ix, Dn, De>,
+) -> Result<(), ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let mut idx_by_id = HashMap::new();
+    loop {
+        let Some(token) = tokens.next()? else {
+            return Err(ParseError::new(tokens.line, 1, "unterminated `graph` list"));
+        };
+        if token.kind == TokenKind::Close {
+            return Ok(());
+        }
+
+        match token.key()? {
+            "node" => {
+                tokens.expect_open(&token)?;
+                let fields = read_list(tokens)?;
+                add_node(g, &mut idx_by_id, &token, &fields)?;
+            }
+            "edge" => {
+                tokens.expect_open(&token)?;
+                let fields = read_list(tokens)?;
+                add_edge(g, &idx_by_id, &token, &fields)?;
+            }
+            _ => {
+                read_value(tokens, &token)?;
+            }
+        }
+    }
+}
+
+fn add_node<Ty, Ix, Dn, De>(
+    g: &mut ImportedGraph<Ty, Ix, Dn, De>,
+    idx_by_id: &mut HashMap<String, NodeIndex<Ix>>,
+    token: &Token,
+    fields: &[(String, Value)],
+) -> Result<(), ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let Some(id) = field(fields, "id") else {
+        return Err(ParseError::new(
+            token.line,
+            token.column,
+            "node without `id`",
+        ));
+    };
+    let id = id.scalar()?.text.clone();
+    let label = match field(fields, "label") {
+        Some(label) => unescape_quotes(&label.scalar()?.text),
+        None => id.clone(),
+    };
+
+    let mut location = None;
+    if let Some(Value::List(_, graphics)) = field(fields, "graphics") {
+        if let (Some(x), Some(y)) = (field(graphics, "x"), field(graphics, "y")) {
+            location = Some(Pos2::new(x.number()?, y.number()?));
+        }
+    }
+
+    if idx_by_id.contains_key(&id) {
+        return Err(ParseError::new(
+            token.line,
+            token.column,
+            format!("duplicate node `{id}`"),
+        ));
+    }
+    check_count::<Ix>(
+        token.line,
+        token.column,
+        g.node_count().checked_add(1),
+        "nodes",
+    )?;
+    let idx = add_node_imported(g, id.clone(), label, location);
+    idx_by_id.insert(id, idx);
+
+    Ok(())
+}
+
+fn add_edge<Ty, Ix, Dn, De>(
+    g: &mut ImportedGraph<Ty, Ix, Dn, De>,
+    idx_by_id: &HashMap<String, NodeIndex<Ix>>,
+    token: &Token,
+    fields: &[(String, Value)],
+) -> Result<(), ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let mut endpoints = [NodeIndex::end(); 2];
+    for (endpoint, key) in endpoints.iter_mut().zip(["source", "target"]) {
+        let Some(value) = field(fields, key) else {
+            return Err(ParseError::new(
+                token.line,
+                token.column,
+                format!("edge without `{key}`"),
+            ));
+        };
+        let scalar = value.scalar()?;
+        let Some(idx) = idx_by_id.get(&scalar.text) else {
+            return Err(ParseError::new(
+                scalar.line,
+                scalar.column,
+                format!("unknown node `{}`", scalar.text),
+            ));
+        };
+        *endpoint = *idx;
+    }
+
+    let weight = match field(fields, "weight").or_else(|| field(fields, "value")) {
+        Some(value) => Some(value.number()?),
+        None => None,
+    };
+
+    check_count::<Ix>(
+        token.line,
+        token.column,
+        g.edge_count().checked_add(1),
+        "edges",
+    )?;
+    let [start, end] = endpoints;
+    match field(fields, "label") {
+        Some(label) => {
+            let label = unescape_quotes(&label.scalar()?.text);
+            g.add_edge_with_label(start, end, weight, label);
+        }
+        None => {
+            g.add_edge(start, end, weight);
+        }
+    }
+
+    Ok(())
+}
+
+fn field<'a>(fields: &'a [(String, Value)], key: &str) -> Option<&'a Value> {
+    fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+/// Reads the value of the key. Lists are read until their closing bracket.
+fn read_value(tokens: &mut Tokens<impl BufRead>, key: &Token) -> Result<Value, ParseError> {
+    let Some(token) = tokens.next()? else {
+        return Err(ParseError::new(key.line, key.column, "expected value"));
+    };
+
+    match token.kind {
+        TokenKind::Open => Ok(Value::List(token, read_list(tokens)?)),
+        TokenKind::Close => Err(ParseError::new(token.line, token.column, "expected value")),
+        TokenKind::Word | TokenKind::Str => Ok(Value::Scalar(token)),
+    }
+}
+
+/// Reads key-value pairs until the closing bracket.
+fn read_list(tokens: &mut Tokens<impl BufRead>) -> Result<Vec<(String, Value)>, ParseError> {
+    let mut res = vec![];
+    loop {
+        let Some(token) = tokens.next()? else {
+            return Err(ParseError::new(tokens.line, 1, "unterminated list"));
+        };
+        if token.kind == TokenKind::Close {
+            return Ok(res);
+        }
+
+        let key = token.key()?.to_string();
+        let value = read_value(tokens, &token)?;
+        res.push((key, value));
+    }
+}
+
+enum Value {
+    Scalar(Token),
+    /// Opening bracket and the key-value pairs of the list.
+    List(Token, Vec<(String, Value)>),
+}
+
+impl Value {
+    fn scalar(&self) -> Result<&Token, ParseError> {
+        match self {
+            Self::Scalar(token) => Ok(token),
+            Self::List(open, _) => Err(ParseError::new(
+                open.line,
+                open.column,
+                "expected value, found list",
+            )),
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&self) -> Result<T, ParseError> {
+        let token = self.scalar()?;
+        parse_token(token.line, token.column, &token.text, "number")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Open,
+    Close,
+    Word,
+    Str,
+}
+
+struct Token {
+    kind: TokenKind,
+    text: String,
+    line: usize,
+    column: usize,
+}
+
+impl Token {
+    fn key(&self) -> Result<&str, ParseError> {
+        if self.kind != TokenKind::Word {
+            return Err(ParseError::new(self.line, self.column, "expected key"));
+        }
+        Ok(&self.text)
+    }
+}
+
+/// Reads tokens line by line.
+struct Tokens<R: BufRead> {
+    lines: Lines<R>,
+    line: usize,
+    pending: VecDeque<Token>,
+}
+
+impl<R: BufRead> Tokens<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, ParseError> {
+        while self.pending.is_empty() {
+            let Some(text) = self.lines.next() else {
+                return Ok(None);
+            };
+            self.line += 1;
+            let line = self.line;
+            let text = text.map_err(|err| ParseError::new(line, 1, err.to_string()))?;
+            if text.trim_start().starts_with('#') {
+                continue;
+            }
+
+            for t in quoted_tokens(&text, line, &['[', ']'])? {
+                let kind = match (t.text, t.quoted) {
+                    (_, true) => TokenKind::Str,
+                    ("[", false) => TokenKind::Open,
+                    ("]", false) => TokenKind::Close,
+                    _ => TokenKind::Word,
+                };
+                self.pending.push_back(Token {
+                    kind,
+                    text: t.text.to_string(),
+                    line,
+                    column: t.column,
+                });
+            }
+        }
+
+        Ok(self.pending.pop_front())
+    }
+
+    fn expect_open(&mut self, key: &Token) -> Result<(), ParseError> {
+        match self.next()? {
+            Some(token) if token.kind == TokenKind::Open => Ok(()),
+            Some(token) => Err(ParseError::new(token.line, token.column, "expected `[`")),
+            None => Err(ParseError::new(key.line, key.column, "expected `[`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::Directed;
+
+    use super::*;
+    use crate::{DefaultEdgeShape, DefaultNodeShape};
+
+    const GML: &str = r#"Creator "test"
+# comment
+graph [
+  directed 1
+  node [ id 1 label "first" graphics [ x 10.5 y -2 w 3 ] ]
+  node [
+    id 2
+  ]
+  edge [ source 1 target 2 value 2.5 ]
+  edge [source 2 target 1 label "back"]
+]
+"#;
+
+    #[test]
+    fn test_graph_from_gml() {
+        let g: ImportedGraph<Directed> = graph_from_gml(GML.as_bytes()).unwrap();
+
+        let nodes = g
+            .nodes_iter()
+            .map(|(_, n)| (n.payload().clone(), n.label()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            vec![
+                ("1".to_string(), "first".to_string()),
+                ("2".to_string(), "2".to_string())
+            ]
+        );
+        assert_eq!(
+            g.node(NodeIndex::new(0)).unwrap().location(),
+            Pos2::new(10.5, -2.)
+        );
+
+        let edges = g
+            .edges_iter()
+            .map(|(_, e)| (*e.payload(), e.label()))
+            .collect::<Vec<_>>();
+        assert_eq!(edges[0].0, Some(2.5));
+        assert_eq!(edges[1], (None, "back".to_string()));
+    }
+
+    #[test]
+    fn test_graph_from_gml_errors() {
+        let parse = |gml: &str| {
+            graph_from_gml::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(gml.as_bytes())
+                .err()
+                .unwrap()
+        };
+
+        let err = parse("graph [\n  node [ id 1 ]\n  edge [ source 1 target 3 ]\n]");
+        assert_eq!((err.line, err.column), (3, 26));
+
+        let err = parse("graph [\n  node [ label \"a\" ]\n]");
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = parse("graph [\n  node [ id 1\n");
+        assert_eq!(err.line, 2);
+
+        let parse_u8 = |gml: &str| {
+            graph_from_gml::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(gml.as_bytes())
+                .err()
+                .unwrap()
+        };
+
+        let nodes = (0..300).fold("graph [\n".to_string(), |gml, i| {
+            gml + "  node [ id " + &i.to_string() + " ]\n"
+        });
+        let err = parse_u8(&nodes);
+        assert_eq!((err.line, err.column), (257, 3));
+
+        let edges = "graph [\n  node [ id 1 ]\n".to_string()
+            + &"  edge [ source 1 target 1 ]\n".repeat(300);
+        let err = parse_u8(&edges);
+        assert_eq!((err.line, err.column), (258, 3));
+    }
+
+    #[test]
+    fn test_write_gml_roundtrip() {
+        let g: ImportedGraph<Directed> = graph_from_gml(GML.as_bytes()).unwrap();
+
+        let mut out = vec![];
+        write_gml_custom(&g, &mut out, |e| *e.payload()).unwrap();
+        let restored: ImportedGraph<Directed> = graph_from_gml(out.as_slice()).unwrap();
+
+        assert_eq!(restored.node_count(), 2);
+        assert_eq!(restored.edge_count(), 2);
+        for ((_, a), (_, b)) in g.nodes_iter().zip(restored.nodes_iter()) {
+            assert_eq!(a.label(), b.label());
+            assert_eq!(a.location(), b.location());
+        }
+        for ((_, a), (_, b)) in g.edges_iter().zip(restored.edges_iter()) {
+            assert_eq!(a.payload(), b.payload());
+        }
+    }
+}
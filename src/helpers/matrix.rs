@@ -0,0 +1,366 @@
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+
+use petgraph::{
+    graph::IndexType,
+    matrix_graph::{MatrixGraph, Nullable},
+    stable_graph::{NodeIndex, StableGraph},
+    visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences},
+    EdgeType,
+};
+
+use crate::{DisplayEdge, DisplayNode, Graph};
+
+use super::ImportedGraph;
+
+use super::{
+    add_node_imported,
+    parse::{check_count, numbered_lines, parse_token, tokens, ParseError},
+    to_graph,
+};
+
+/// Max number of nodes which are not referenced by any entry of a sparse matrix. Larger indices
+/// are rejected to not allocate nodes for malformed input.
+const MAX_INDEX_GAP: usize = 1 << 20;
+
+/// Reads a dense adjacency matrix and builds a [`Graph`] from it.
+///
+/// Every non-empty line is a row of the matrix, values are separated by whitespace or commas.
+/// Lines starting with `#` are skipped. Non-zero value in row `i` and column `j` produces an edge
+/// from node `i` to node `j` with the value as the edge weight. For undirected graphs the matrix is
+/// treated as symmetric: a value below the diagonal is skipped when its mirrored value above the
+/// diagonal is non-zero, so every pair of nodes gets one edge. [`graph_from_sparse_matrix`] reads
+/// undirected graphs the same way.
+///
+/// Nodes payloads and labels are their zero-based positions in the matrix.
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if a value is not a number, the matrix is not square or the nodes or edges
+/// do not fit into the index type of the graph.
+pub fn graph_from_dense_matrix<Ty, Ix, Dn, De>(
+    reader: impl BufRead,
+) -> Result<ImportedGraph<Ty, Ix, Dn, De>, ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let mut rows: Vec<Vec<f64>> = vec![];
+    let mut lines = vec![];
+    let mut last_line = 0;
+    for line in numbered_lines(reader) {
+        let (line, text) = line?;
+        last_line = line;
+        if text.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let row_tokens = tokens(&text);
+        if row_tokens.is_empty() {
+            continue;
+        }
+
+        check_count::<Ix>(line, 1, rows.len().checked_add(1), "nodes")?;
+        let row = row_tokens
+            .iter()
+            .map(|(column, token)| parse_token(line, *column, token, "value"))
+            .collect::<Result<Vec<f64>, _>>()?;
+
+        if let Some(first) = rows.first() {
+            if first.len() != row.len() {
+                return Err(ParseError::new(
+                    line,
+                    1,
+                    format!(
+                        "expected {} values in the row, found {}",
+                        first.len(),
+                        row.len()
+                    ),
+                ));
+            }
+        }
+
+        rows.push(row);
+        lines.push((line, row_tokens.iter().map(|(c, _)| *c).collect::<Vec<_>>()));
+    }
+
+    let size = rows.first().map_or(0, Vec::len);
+    if rows.len() != size {
+        return Err(ParseError::new(
+            last_line,
+            1,
+            format!("matrix is not square: {} rows, {size} columns", rows.len()),
+        ));
+    }
+
+    let mut g = Graph::new(StableGraph::default());
+    let nodes = (0..size)
+        .map(|i| add_node_imported(&mut g, i.to_string(), i.to_string(), None))
+        .collect::<Vec<_>>();
+
+    let directed = g.is_directed();
+    for (i, row) in rows.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            if *value == 0. || (!directed && j < i && rows[j][i] != 0.) {
+                continue;
+            }
+            let (line, columns) = &lines[i];
+            check_count::<Ix>(*line, columns[j], g.edge_count().checked_add(1), "edges")?;
+            g.add_edge(nodes[i], nodes[j], Some(*value));
+        }
+    }
+
+    Ok(g)
+}
+
+/// Reads a sparse adjacency matrix in coordinate format and builds a [`Graph`] from it.
+///
+/// Every non-empty line is an entry `row col [value]` with zero-based row and column, separated by
+/// whitespace or commas. Missing value means `1`. Lines starting with `#` or `%` are skipped.
+/// Every entry produces an edge from node `row` to node `col`. For undirected graphs an entry below
+/// the diagonal is skipped when the mirrored entry above the diagonal exists, the same way
+/// [`graph_from_dense_matrix`] reads them. The graph gets as many nodes as needed to cover the
+/// largest index.
+///
+/// Nodes payloads and labels are their zero-based positions in the matrix.
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if an entry is malformed, an index leaves more than a million nodes
+/// without entries or the nodes or edges do not fit into the index type of the graph.
+pub fn graph_from_sparse_matrix<Ty, Ix, Dn, De>(
+    reader: impl BufRead,
+) -> Result<ImportedGraph<Ty, Ix, Dn, De>, ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let mut entries = vec![];
+    let mut size = 0;
+    for line in numbered_lines(reader) {
+        let (line, text) = line?;
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('#') || trimmed.starts_with('%') {
+            continue;
+        }
+
+        let entry = tokens(&text);
+        let (row, col, value) = match entry.as_slice() {
+            [] => continue,
+            [row, col] => (row, col, None),
+            [row, col, value] => (row, col, Some(value)),
+            _ => {
+                return Err(ParseError::new(
+                    line,
+                    1,
+                    format!("expected `row col [value]`, found {} values", entry.len()),
+                ))
+            }
+        };
+
+        let (row_column, col_column) = (row.0, col.0);
+        let row: usize = parse_token(line, row_column, row.1, "row")?;
+        let col: usize = parse_token(line, col_column, col.1, "column")?;
+        let value = match value {
+            Some((column, token)) => parse_token(line, *column, token, "value")?,
+            None => 1.,
+        };
+
+        // every entry references at most two nodes, larger indices leave holes
+        let max_size = entries.len().saturating_add(1).saturating_mul(2) + MAX_INDEX_GAP;
+        for (index, column) in [(row, row_column), (col, col_column)] {
+            let count = check_count::<Ix>(line, column, index.checked_add(1), "nodes")?;
+            if count > max_size {
+                return Err(ParseError::new(
+                    line,
+                    column,
+                    format!("index {index} leaves too many nodes without entries"),
+                ));
+            }
+            size = size.max(count);
+        }
+        entries.push((line, row, col, value));
+    }
+
+    let mut g = Graph::new(StableGraph::default());
+    let nodes = (0..size)
+        .map(|i| add_node_imported(&mut g, i.to_string(), i.to_string(), None))
+        .collect::<Vec<_>>();
+
+    let upper = if g.is_directed() {
+        HashSet::new()
+    } else {
+        entries
+            .iter()
+            .filter(|(_, row, col, _)| row <= col)
+            .map(|(_, row, col, _)| (*row, *col))
+            .collect()
+    };
+    for (line, row, col, value) in entries {
+        if row > col && upper.contains(&(col, row)) {
+            continue;
+        }
+        check_count::<Ix>(line, 1, g.edge_count().checked_add(1), "edges")?;
+        g.add_edge(nodes[row], nodes[col], Some(value));
+    }
+
+    Ok(g)
+}
+
+/// Transforms [`petgraph::matrix_graph::MatrixGraph`] into the [`Graph`] the same way [`to_graph`] does.
+pub fn from_matrix_graph<N, E, Ty, Null, Ix, Dn, De>(
+    g: &MatrixGraph<N, E, Ty, Null, Ix>,
+) -> Graph<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Null: Nullable<Wrapped = E>,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let mut stable = StableGraph::with_capacity(g.node_count(), g.edge_count());
+    let idx_by_matrix_idx = g
+        .node_references()
+        .map(|(idx, n)| (idx, stable.add_node(n.clone())))
+        .collect::<HashMap<NodeIndex<Ix>, NodeIndex<Ix>>>();
+
+    for e in g.edge_references() {
+        stable.add_edge(
+            idx_by_matrix_idx[&e.source()],
+            idx_by_matrix_idx[&e.target()],
+            e.weight().clone(),
+        );
+    }
+
+    to_graph(&stable)
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{Directed, Undirected};
+
+    use super::*;
+    use crate::{DefaultEdgeShape, DefaultNodeShape};
+
+    #[test]
+    fn test_graph_from_dense_matrix() {
+        let matrix = "# adjacency\n0 1 0\n1, 0, 2.5\n0 0 1\n";
+
+        let directed: ImportedGraph<Directed> = graph_from_dense_matrix(matrix.as_bytes()).unwrap();
+        let undirected: ImportedGraph<Undirected> =
+            graph_from_dense_matrix(matrix.as_bytes()).unwrap();
+
+        assert_eq!(directed.node_count(), 3);
+        assert_eq!(directed.edge_count(), 4);
+        assert_eq!(undirected.edge_count(), 3);
+
+        // value below the diagonal without the mirrored one
+        let undirected: ImportedGraph<Undirected> =
+            graph_from_dense_matrix("0 0\n3 0\n".as_bytes()).unwrap();
+        assert_eq!(undirected.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_graph_from_dense_matrix_not_square() {
+        let err = graph_from_dense_matrix::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(
+            "0 1\n1 0 1\n".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.line, 2);
+
+        let err = graph_from_dense_matrix::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(
+            "0 1\n1 x\n".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let matrix = "0 ".repeat(300).trim_end().to_string() + "\n";
+        let err = graph_from_dense_matrix::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(
+            matrix.repeat(300).as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (256, 1));
+
+        let matrix = "1 ".repeat(20).trim_end().to_string() + "\n";
+        let err = graph_from_dense_matrix::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(
+            matrix.repeat(20).as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (13, 31));
+    }
+
+    #[test]
+    fn test_graph_from_sparse_matrix() {
+        let matrix = "% coordinates\n0 1\n1 3 0.5\n";
+
+        let g: ImportedGraph<Directed> = graph_from_sparse_matrix(matrix.as_bytes()).unwrap();
+
+        assert_eq!(g.node_count(), 4);
+        assert_eq!(g.edge_count(), 2);
+        let weights = g
+            .edges_iter()
+            .map(|(_, e)| *e.payload())
+            .collect::<Vec<_>>();
+        assert_eq!(weights, vec![Some(1.), Some(0.5)]);
+
+        let g: ImportedGraph<Undirected> =
+            graph_from_sparse_matrix("0 1\n1 0\n2 1\n".as_bytes()).unwrap();
+        assert_eq!(g.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_graph_from_sparse_matrix_errors() {
+        let err = graph_from_sparse_matrix::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(
+            "0 1\n0 18446744073709551615\n".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = graph_from_sparse_matrix::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(
+            "300 0\n".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let edges = "0 1\n".repeat(300);
+        let err = graph_from_sparse_matrix::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(
+            edges.as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.line, 256);
+
+        let err = graph_from_sparse_matrix::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(
+            "0 1\n0 4294967294\n".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn test_from_matrix_graph() {
+        let mut m: MatrixGraph<&str, &str> = MatrixGraph::new();
+        let a = m.add_node("a");
+        let b = m.add_node("b");
+        m.add_edge(a, b, "ab");
+        m.add_edge(b, b, "bb");
+
+        let g: Graph<&str, &str, Directed, u16> = from_matrix_graph(&m);
+
+        assert_eq!(g.node_count(), 2);
+        assert_eq!(g.edge_count(), 2);
+    }
+}
@@ -0,0 +1,710 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io::BufRead;
+
+use petgraph::{
+    graph::IndexType,
+    stable_graph::{NodeIndex, StableGraph},
+    EdgeType,
+};
+
+use crate::{DisplayEdge, DisplayNode, Graph, Node};
+
+use super::{
+    add_node_imported,
+    parse::{check_count, numbered_lines, ParseError},
+};
+
+/// Direction of a Mermaid flowchart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MermaidDirection {
+    #[default]
+    TopDown,
+    BottomUp,
+    LeftRight,
+    RightLeft,
+}
+
+impl MermaidDirection {
+    fn keyword(self) -> &'static str {
+        match self {
+            Self::TopDown => "TD",
+            Self::BottomUp => "BT",
+            Self::LeftRight => "LR",
+            Self::RightLeft => "RL",
+        }
+    }
+}
+
+/// Mermaid flowchart node shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MermaidShape {
+    /// `id[text]`
+    Rectangle,
+    /// `id(text)`
+    Rounded,
+    /// `id([text])`
+    Stadium,
+    /// `id[[text]]`
+    Subroutine,
+    /// `id[(text)]`
+    Cylinder,
+    /// `id((text))`
+    #[default]
+    Circle,
+    /// `id(((text)))`
+    DoubleCircle,
+    /// `id>text]`
+    Asymmetric,
+    /// `id{text}`
+    Rhombus,
+    /// `id{{text}}`
+    Hexagon,
+}
+
+impl MermaidShape {
+    /// Openers are ordered so that the longer ones are checked first.
+    const ALL: [(Self, &'static str, &'static str); 10] = [
+        (Self::DoubleCircle, "(((", ")))"),
+        (Self::Stadium, "([", "])"),
+        (Self::Circle, "((", "))"),
+        (Self::Subroutine, "[[", "]]"),
+        (Self::Cylinder, "[(", ")]"),
+        (Self::Hexagon, "{{", "}}"),
+        (Self::Rectangle, "[", "]"),
+        (Self::Rounded, "(", ")"),
+        (Self::Rhombus, "{", "}"),
+        (Self::Asymmetric, ">", "]"),
+    ];
+
+    fn delimiters(self) -> (&'static str, &'static str) {
+        Self::ALL
+            .iter()
+            .find(|(shape, _, _)| *shape == self)
+            .map_or(("[", "]"), |(_, open, close)| (*open, *close))
+    }
+}
+
+/// Options for [`graph_to_mermaid`].
+#[derive(Debug, Clone, Default)]
+pub struct MermaidExportOptions {
+    pub(crate) direction: MermaidDirection,
+    pub(crate) edge_labels: bool,
+}
+
+impl MermaidExportOptions {
+    /// Creates new [`MermaidExportOptions`] with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default: [`MermaidDirection::TopDown`]
+    pub fn with_direction(mut self, direction: MermaidDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Whether edge labels are written. Disabled by default because edges get generated labels
+    /// like `edge 0` when created without one.
+    ///
+    /// Default: `false`
+    pub fn with_edge_labels(mut self, enabled: bool) -> Self {
+        self.edge_labels = enabled;
+        self
+    }
+}
+
+/// Writes the [`Graph`] as a Mermaid flowchart.
+///
+/// Nodes get ids `n0`, `n1`, ... by their indices and are written with their labels as text.
+/// Directed graphs use `-->` links, undirected graphs use `---`. All nodes are drawn as circles
+/// which is the closest shape to [`crate::DefaultNodeShape`], use [`graph_to_mermaid_custom`]
+/// to choose shapes per node.
+///
+/// ```
+/// use egui_graphs::{graph_to_mermaid, Graph, MermaidExportOptions};
+/// use petgraph::stable_graph::StableGraph;
+///
+/// let mut g: Graph = Graph::new(StableGraph::default());
+/// let a = g.add_node_with_label((), "a".to_string());
+/// let b = g.add_node_with_label((), "b".to_string());
+/// g.add_edge(a, b, ());
+///
+/// let chart = graph_to_mermaid(&g, &MermaidExportOptions::new());
+/// assert_eq!(chart, "flowchart TD\n    n0((\"a\"))\n    n1((\"b\"))\n    n0 --> n1\n");
+/// ```
+pub fn graph_to_mermaid<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+    options: &MermaidExportOptions,
+) -> String
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    graph_to_mermaid_custom(g, options, |_| MermaidShape::Circle)
+}
+
+/// Writes the [`Graph`] as a Mermaid flowchart using custom function to pick the node shapes.
+pub fn graph_to_mermaid_custom<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+    options: &MermaidExportOptions,
+    node_shape: impl Fn(&Node<N, E, Ty, Ix, Dn>) -> MermaidShape,
+) -> String
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let mut res = format!("flowchart {}\n", options.direction.keyword());
+
+    for (idx, n) in g.nodes_iter() {
+        let (open, close) = node_shape(n).delimiters();
+        let mut text = n.label();
+        if text.is_empty() {
+            text = format!("n{}", idx.index());
+        }
+        // writing to a string never fails
+        let _ = writeln!(
+            res,
+            "    n{}{open}{}{close}",
+            idx.index(),
+            quoted_text(&text)
+        );
+    }
+
+    let link = if g.is_directed() { "-->" } else { "---" };
+    for (idx, e) in g.edges_iter() {
+        let Some((start, end)) = g.edge_endpoints(idx) else {
+            continue;
+        };
+        let label = e.label();
+        let _ = if options.edge_labels && !label.is_empty() {
+            writeln!(
+                res,
+                "    n{} {link}|{}| n{}",
+                start.index(),
+                quoted_text(&label),
+                end.index()
+            )
+        } else {
+            writeln!(res, "    n{} {link} n{}", start.index(), end.index())
+        };
+    }
+
+    res
+}
+
+/// Reads a Mermaid flowchart and builds a [`Graph`] from it.
+///
+/// Supported is the subset of the syntax needed to describe the graph structure: the `flowchart` or
+/// `graph` header, node declarations with any shape, links of any style including circle `--o`,
+/// cross `--x` and invisible `~~~` links with optional text in `A -->|text| B` or
+/// `A -- text --> B` form, link chains `A --> B --> C` and node groups `A & B --> C`. Statements
+/// can be separated by new lines or `;`. Comments, subgraph borders, styling and interaction
+/// statements are skipped.
+///
+/// Node payloads are Mermaid node ids, labels are node texts or ids when the text is missing. Edges
+/// are added in the order of the links and keep the link text as a label. Direction of the graph
+/// is defined by `Ty`, arrow heads are not taken into account.
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if the header is missing, a statement could not be parsed or the nodes or
+/// edges do not fit into the index type of the graph.
+pub fn graph_from_mermaid<Ty, Ix, Dn, De>(
+    reader: impl BufRead,
+) -> Result<Graph<String, (), Ty, Ix, Dn, De>, ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, (), Ty, Ix>,
+    De: DisplayEdge<String, (), Ty, Ix, Dn>,
+{
+    let mut g = Graph::new(StableGraph::default());
+    let mut idx_by_id = HashMap::new();
+    let mut has_header = false;
+
+    for line in numbered_lines(reader) {
+        let (line, text) = line?;
+        for (column, statement) in statements(&text) {
+            let trimmed = statement.trim();
+            if trimmed.is_empty() || trimmed.starts_with("%%") {
+                continue;
+            }
+
+            if !has_header {
+                let keyword = trimmed.split_whitespace().next().unwrap_or_default();
+                if keyword != "flowchart" && keyword != "graph" {
+                    return Err(ParseError::new(
+                        line,
+                        column,
+                        "expected `flowchart` or `graph` header",
+                    ));
+                }
+                has_header = true;
+                continue;
+            }
+
+            if is_skipped(trimmed) {
+                continue;
+            }
+
+            let parsed = Statement::parse(statement, line, column)?;
+            add_statement(&mut g, &mut idx_by_id, parsed)?;
+        }
+    }
+
+    if !has_header {
+        return Err(ParseError::new(
+            1,
+            1,
+            "expected `flowchart` or `graph` header",
+        ));
+    }
+
+    Ok(g)
+}
+
+fn quoted_text(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;"))
+}
+
+fn unquoted_text(text: &str) -> String {
+    let text = text.trim();
+    let text = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text);
+    text.replace("#quot;", "\"")
+}
+
+fn is_skipped(statement: &str) -> bool {
+    const KEYWORDS: [&str; 8] = [
+        "subgraph",
+        "end",
+        "direction",
+        "style",
+        "classDef",
+        "class",
+        "click",
+        "linkStyle",
+    ];
+    let keyword = statement.split_whitespace().next().unwrap_or_default();
+    KEYWORDS.contains(&keyword)
+}
+
+fn add_statement<Ty, Ix, Dn, De>(
+    g: &mut Graph<String, (), Ty, Ix, Dn, De>,
+    idx_by_id: &mut HashMap<String, NodeIndex<Ix>>,
+    statement: Statement,
+) -> Result<(), ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, (), Ty, Ix>,
+    De: DisplayEdge<String, (), Ty, Ix, Dn>,
+{
+    let line = statement.line;
+    let mut groups = vec![];
+    for group in statement.groups {
+        let mut nodes = vec![];
+        for decl in group {
+            let idx = if let Some(idx) = idx_by_id.get(&decl.id) {
+                *idx
+            } else {
+                check_count::<Ix>(line, decl.column, g.node_count().checked_add(1), "nodes")?;
+                let idx = add_node_imported(g, decl.id.clone(), decl.id.clone(), None);
+                idx_by_id.insert(decl.id, idx);
+                idx
+            };
+            if let Some(text) = decl.text {
+                if let Some(n) = g.node_mut(idx) {
+                    n.set_label(text);
+                }
+            }
+            nodes.push(idx);
+        }
+        groups.push(nodes);
+    }
+
+    for (i, (column, label)) in statement.links.into_iter().enumerate() {
+        for start in &groups[i] {
+            for end in &groups[i + 1] {
+                check_count::<Ix>(line, column, g.edge_count().checked_add(1), "edges")?;
+                match &label {
+                    Some(label) => g.add_edge_with_label(*start, *end, (), label.clone()),
+                    None => g.add_edge(*start, *end, ()),
+                };
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits the line into statements separated by `;` which are not part of a text.
+/// Returns statements together with their 1-based columns.
+fn statements(line: &str) -> Vec<(usize, &str)> {
+    let mut res = vec![];
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (byte_idx, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                res.push((line[..start].chars().count() + 1, &line[start..byte_idx]));
+                start = byte_idx + 1;
+            }
+            _ => {}
+        }
+    }
+    res.push((line[..start].chars().count() + 1, &line[start..]));
+
+    res
+}
+
+struct NodeDecl {
+    id: String,
+    /// 1-based column of the id in the line.
+    column: usize,
+    text: Option<String>,
+}
+
+/// Parsed statement: groups of nodes separated by links. There is always one link less than
+/// groups.
+struct Statement {
+    line: usize,
+    groups: Vec<Vec<NodeDecl>>,
+    /// Link texts together with 1-based columns of the links in the line.
+    links: Vec<(usize, Option<String>)>,
+}
+
+impl Statement {
+    fn parse(statement: &str, line: usize, column: usize) -> Result<Self, ParseError> {
+        let mut cursor = Cursor {
+            chars: statement.chars().collect(),
+            pos: 0,
+            line,
+            column,
+        };
+
+        let mut res = Self {
+            line,
+            groups: vec![cursor.node_group()?],
+            links: vec![],
+        };
+        loop {
+            cursor.skip_whitespace();
+            if cursor.is_done() {
+                break;
+            }
+            let column = cursor.column + cursor.pos;
+            res.links.push((column, cursor.link()?));
+            res.groups.push(cursor.node_group()?);
+        }
+
+        Ok(res)
+    }
+}
+
+const LINK_CHARS: [char; 6] = ['-', '=', '.', '~', '<', '>'];
+
+/// Circle and cross heads which can start or end a link, e.g. `o--o` or `--x`.
+const LINK_HEADS: [char; 2] = ['o', 'x'];
+
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Cursor {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, self.column + self.pos, message)
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn node_group(&mut self) -> Result<Vec<NodeDecl>, ParseError> {
+        let mut res = vec![self.node()?];
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('&') {
+                return Ok(res);
+            }
+            self.pos += 1;
+            res.push(self.node()?);
+        }
+    }
+
+    fn node(&mut self) -> Result<NodeDecl, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected node id"));
+        }
+        let id = self.chars[start..self.pos].iter().collect::<String>();
+
+        let mut text = None;
+        if let Some((_, open, close)) = MermaidShape::ALL
+            .iter()
+            .find(|(_, open, _)| self.starts_with(open))
+        {
+            self.pos += open.chars().count();
+            text = Some(unquoted_text(&self.until(close)?));
+        }
+
+        // class shorthand `id:::class`
+        if self.starts_with(":::") {
+            while self.peek().is_some_and(|c| !c.is_whitespace() && c != '&') {
+                self.pos += 1;
+            }
+        }
+
+        Ok(NodeDecl {
+            id,
+            column: self.column + start,
+            text,
+        })
+    }
+
+    /// Reads a link and returns its text if any.
+    fn link(&mut self) -> Result<Option<String>, ParseError> {
+        let start = self.pos;
+        let arrow = self.link_chars(true);
+        let is_complete = arrow.ends_with(['>', 'o', 'x'])
+            || arrow.trim_start_matches(['<', 'o', 'x']).chars().count() >= 3;
+        if arrow.chars().count() < 2 {
+            self.pos = start;
+            return Err(self.error("expected link"));
+        }
+
+        if is_complete {
+            if self.peek() == Some('|') {
+                self.pos += 1;
+                return Ok(Some(unquoted_text(&self.until("|")?)));
+            }
+            return Ok(None);
+        }
+
+        // text between the link parts `A -- text --> B`
+        let text_start = self.pos;
+        while !self.is_done() {
+            let part_start = self.pos;
+            let part = self.link_chars(false);
+            if part.contains('>') || part.chars().count() >= 2 {
+                let text = self.chars[text_start..part_start]
+                    .iter()
+                    .collect::<String>();
+                return Ok(Some(unquoted_text(&text)));
+            }
+            self.pos = part_start + 1;
+        }
+
+        self.pos = start;
+        Err(self.error("unterminated link text"))
+    }
+
+    /// Reads link characters. Circle and cross heads are read only next to the link lines, the
+    /// starting head only if `with_start_head` is set, so they are not confused with node ids.
+    fn link_chars(&mut self, with_start_head: bool) -> String {
+        let is_line = |c: Option<&char>| c.is_some_and(|c| ['-', '=', '.'].contains(c));
+        let start = self.pos;
+        if with_start_head
+            && self.peek().is_some_and(|c| LINK_HEADS.contains(&c))
+            && is_line(self.chars.get(self.pos + 1))
+        {
+            self.pos += 1;
+        }
+        let body_start = self.pos;
+        while self.peek().is_some_and(|c| LINK_CHARS.contains(&c)) {
+            self.pos += 1;
+        }
+        if self.pos - body_start >= 2
+            && is_line(self.chars.get(self.pos - 1))
+            && self.peek().is_some_and(|c| LINK_HEADS.contains(&c))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Reads text until the closing pattern which is not inside quotes and skips the pattern.
+    fn until(&mut self, close: &str) -> Result<String, ParseError> {
+        let start = self.pos;
+        let mut in_quotes = false;
+        while !self.is_done() {
+            if !in_quotes && self.starts_with(close) {
+                let text = self.chars[start..self.pos].iter().collect();
+                self.pos += close.chars().count();
+                return Ok(text);
+            }
+            if self.peek() == Some('"') {
+                in_quotes = !in_quotes;
+            }
+            self.pos += 1;
+        }
+
+        self.pos = start;
+        Err(self.error(format!("expected `{close}`")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{Directed, Undirected};
+
+    use super::*;
+    use crate::{DefaultEdgeShape, DefaultNodeShape};
+
+    type MermaidGraph<Ty> = Graph<String, (), Ty, u32, DefaultNodeShape, DefaultEdgeShape>;
+
+    fn labels<Ty: EdgeType>(g: &MermaidGraph<Ty>) -> Vec<(String, String)> {
+        g.nodes_iter()
+            .map(|(_, n)| (n.payload().clone(), n.label()))
+            .collect()
+    }
+
+    #[test]
+    fn test_graph_from_mermaid() {
+        let chart = r#"
+%% docs diagram
+flowchart LR
+    A[Start] --> B{"Is it; ok?"}
+    B -->|yes| C([Done]) --- D:::leaf
+    B -- no --> A
+    E & F ==> C; style A fill:#f9f
+"#;
+
+        let g: MermaidGraph<Directed> = graph_from_mermaid(chart.as_bytes()).unwrap();
+
+        assert_eq!(
+            labels(&g),
+            vec![
+                ("A".to_string(), "Start".to_string()),
+                ("B".to_string(), "Is it; ok?".to_string()),
+                ("C".to_string(), "Done".to_string()),
+                ("D".to_string(), "D".to_string()),
+                ("E".to_string(), "E".to_string()),
+                ("F".to_string(), "F".to_string()),
+            ]
+        );
+        assert_eq!(g.edge_count(), 6);
+        let edge_labels = g
+            .edges_iter()
+            .map(|(_, e)| e.label())
+            .filter(|l| !l.starts_with("edge "))
+            .collect::<Vec<_>>();
+        assert_eq!(edge_labels, vec!["yes", "no"]);
+    }
+
+    #[test]
+    fn test_graph_from_mermaid_link_heads() {
+        for link in [
+            "--o",
+            "--x",
+            "o--o",
+            "x--x",
+            "~~~",
+            "-- text --o",
+            "o-- text --o",
+        ] {
+            let chart = format!("graph\nA {link} B\nB-->oC");
+            let g: MermaidGraph<Directed> = graph_from_mermaid(chart.as_bytes()).unwrap();
+
+            let ids = labels(&g).into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+            assert_eq!(ids, vec!["A", "B", "oC"], "{link}");
+            assert_eq!(g.edge_count(), 2, "{link}");
+            let has_text = g.edges_iter().any(|(_, e)| e.label() == "text");
+            assert_eq!(has_text, link.contains("text"), "{link}");
+        }
+    }
+
+    #[test]
+    fn test_graph_from_mermaid_errors() {
+        let err = graph_from_mermaid::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(
+            "A --> B".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let err = graph_from_mermaid::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(
+            "graph TD\n  A[oops --> B".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (2, 5));
+
+        let chart = (0..300).fold("graph".to_string(), |chart, i| {
+            chart + "\nA --> n" + &i.to_string()
+        });
+        let err = graph_from_mermaid::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(
+            chart.as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (256, 7));
+
+        let chart = "graph\n".to_string() + &"A --> B\n".repeat(300);
+        let err = graph_from_mermaid::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(
+            chart.as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (257, 3));
+    }
+
+    #[test]
+    fn test_graph_to_mermaid_roundtrip() {
+        let chart = "graph\nA((\"say #quot;hi#quot;\")) --- B\n";
+        let g: MermaidGraph<Undirected> = graph_from_mermaid(chart.as_bytes()).unwrap();
+
+        let exported = graph_to_mermaid_custom(
+            &g,
+            &MermaidExportOptions::new().with_direction(MermaidDirection::LeftRight),
+            |n| {
+                if n.payload() == "A" {
+                    MermaidShape::Hexagon
+                } else {
+                    MermaidShape::Rectangle
+                }
+            },
+        );
+
+        assert_eq!(
+            exported,
+            "flowchart LR\n    n0{{\"say #quot;hi#quot;\"}}\n    n1[\"B\"]\n    n0 --- n1\n"
+        );
+    }
+}
@@ -1,14 +1,43 @@
-use crate::{DisplayEdge, DisplayNode, Edge, Graph, Node};
-use egui::Vec2;
+mod csv;
+mod gml;
+mod matrix;
+mod mermaid;
+mod pajek;
+mod parse;
+
+pub use csv::{
+    edge_list_from_csv, node_attributes_from_csv, CsvColumn, CsvEdgeListOptions,
+    CsvNodeAttributesOptions,
+};
+pub use gml::{graph_from_gml, write_gml, write_gml_custom};
+pub use matrix::{from_matrix_graph, graph_from_dense_matrix, graph_from_sparse_matrix};
+pub use mermaid::{
+    graph_from_mermaid, graph_to_mermaid, graph_to_mermaid_custom, MermaidDirection,
+    MermaidExportOptions, MermaidShape,
+};
+pub use pajek::{graph_from_pajek, write_pajek, write_pajek_custom};
+pub use parse::ParseError;
+
+use crate::{DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, Edge, Graph, Node};
+use egui::{Pos2, Vec2};
 use petgraph::{
     graph::IndexType,
-    stable_graph::{EdgeIndex, NodeIndex, StableGraph},
+    stable_graph::{DefaultIx, EdgeIndex, NodeIndex, StableGraph},
     visit::IntoNodeReferences,
-    EdgeType,
+    Directed, EdgeType,
 };
 use rand::Rng;
 use std::collections::HashMap;
 
+/// Graph produced by the importers which read plain text formats. Node payloads are the node ids
+/// from the input and edge payloads are the optional edge weights.
+pub type ImportedGraph<
+    Ty = Directed,
+    Ix = DefaultIx,
+    Dn = DefaultNodeShape,
+    De = DefaultEdgeShape,
+> = Graph<String, Option<f64>, Ty, Ix, Dn, De>;
+
 /// Helper function which adds user's node to the [`super::Graph`] instance.
 ///
 /// If graph is not empty it picks any node position and adds new node in the vicinity of it.
@@ -47,7 +76,9 @@ where
 
     node_transform(&mut n);
 
-    g.g.add_node(n)
+    let idx = g.g.add_node(n);
+    g.mark_node_dirty(idx);
+    idx
 }
 
 /// Helper function which adds user's edge to the [`super::Graph`] instance.
@@ -97,7 +128,9 @@ where
 
     edge_transform(&mut edge);
 
-    g.g.add_edge(start, end, edge)
+    let idx = g.g.add_edge(start, end, edge);
+    g.mark_edge_dirty(idx);
+    idx
 }
 
 /// Helper function which transforms [`petgraph::stable_graph::StableGraph`] into the [`super::Graph`] required by the [`super::GraphView`] widget.
@@ -269,6 +302,35 @@ pub fn random_graph(num_nodes: usize, num_edges: usize) -> Graph {
     to_graph(&graph)
 }
 
+/// Adds node created by one of the importers. Nodes without location are left to be placed by the layout.
+#[allow(clippy::missing_panics_doc)]
+pub(crate) fn add_node_imported<N, E, Ty, Ix, Dn, De>(
+    g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    payload: N,
+    label: String,
+    location: Option<Pos2>,
+) -> NodeIndex<Ix>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    if let Some(loc) = location {
+        return g.add_node_with_label_and_location(payload, label, loc);
+    }
+
+    let idx = g.g.add_node(Node::new(payload));
+    let n = g.g.node_weight_mut(idx).unwrap();
+    n.set_id(idx);
+    n.set_label(label);
+    g.mark_node_dirty(idx);
+
+    idx
+}
+
 #[cfg(test)]
 mod tests {
     use crate::DefaultEdgeShape;
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use egui::Pos2;
+use petgraph::{
+    graph::IndexType,
+    stable_graph::{NodeIndex, StableGraph},
+    EdgeType,
+};
+
+use crate::{DisplayEdge, DisplayNode, Edge, Graph};
+
+use super::{
+    add_node_imported,
+    parse::{
+        check_count, escape_quotes, numbered_lines, parse_token, quoted_tokens, unescape_quotes,
+        ParseError, QuotedToken,
+    },
+    ImportedGraph,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    None,
+    Vertices,
+    Edges,
+    EdgesList,
+    Skipped,
+}
+
+/// Reads a graph in Pajek `.net` format and builds a [`Graph`] from it.
+///
+/// The input is read line by line and every vertex and edge is added to the graph as soon as it is
+/// parsed. Supported sections are `*Vertices`, `*Arcs`, `*Edges`, `*Arcslist` and `*Edgeslist`,
+/// other sections are skipped. Nodes get their numbers as payload and labels as labels, the first two
+/// coordinates are used as location when present. Pajek coordinates are usually normalized to `[0, 1]`
+/// and are taken as is. Edges get their weight, if present, as payload. Direction of the graph is
+/// defined by `Ty`, arcs and edges are read the same way.
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if a line is malformed, an edge references a vertex which is not declared
+/// or the vertices or edges do not fit into the index type of the graph.
+pub fn graph_from_pajek<Ty, Ix, Dn, De>(
+    reader: impl BufRead,
+) -> Result<ImportedGraph<Ty, Ix, Dn, De>, ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let mut g = Graph::new(StableGraph::default());
+    let mut idx_by_id = HashMap::new();
+    let mut vertices_count = 0;
+    let mut section = Section::None;
+
+    for line in numbered_lines(reader) {
+        let (line, text) = line?;
+        let tokens = quoted_tokens(&text, line, &[])?;
+        let Some(first) = tokens.first() else {
+            continue;
+        };
+        if !first.quoted && first.text.starts_with('%') {
+            continue;
+        }
+
+        if !first.quoted && first.text.starts_with('*') {
+            if section == Section::Vertices {
+                add_undeclared_vertices(&mut g, &mut idx_by_id, vertices_count);
+            }
+
+            section = match first.text.to_lowercase().as_str() {
+                "*vertices" => {
+                    let Some(count) = tokens.get(1) else {
+                        return Err(ParseError::new(line, first.column, "expected vertex count"));
+                    };
+                    let parsed = parse_token(line, count.column, count.text, "vertex count")?;
+                    vertices_count =
+                        check_count::<Ix>(line, count.column, Some(parsed), "vertices")?;
+                    Section::Vertices
+                }
+                "*arcs" | "*edges" => Section::Edges,
+                "*arcslist" | "*edgeslist" => Section::EdgesList,
+                _ => Section::Skipped,
+            };
+            continue;
+        }
+
+        match section {
+            Section::None => {
+                return Err(ParseError::new(line, first.column, "expected section"));
+            }
+            Section::Vertices => {
+                add_vertex(&mut g, &mut idx_by_id, vertices_count, line, &tokens)?;
+            }
+            Section::Edges => {
+                let [source, target, ..] = tokens.as_slice() else {
+                    return Err(ParseError::new(line, first.column, "expected two vertices"));
+                };
+                let weight = match tokens.get(2) {
+                    Some(w) => Some(parse_token(line, w.column, w.text, "weight")?),
+                    None => None,
+                };
+                let start = vertex(&idx_by_id, line, source)?;
+                let end = vertex(&idx_by_id, line, target)?;
+                check_count::<Ix>(line, first.column, g.edge_count().checked_add(1), "edges")?;
+                g.add_edge(start, end, weight);
+            }
+            Section::EdgesList => {
+                let start = vertex(&idx_by_id, line, first)?;
+                for target in &tokens[1..] {
+                    let end = vertex(&idx_by_id, line, target)?;
+                    check_count::<Ix>(line, target.column, g.edge_count().checked_add(1), "edges")?;
+                    g.add_edge(start, end, None);
+                }
+            }
+            Section::Skipped => {}
+        }
+    }
+
+    if section == Section::Vertices {
+        add_undeclared_vertices(&mut g, &mut idx_by_id, vertices_count);
+    }
+
+    Ok(g)
+}
+
+/// Writes the [`Graph`] in Pajek `.net` format.
+///
+/// Vertices are numbered from `1` in the order of node indices and are written with labels and
+/// locations. Directed graphs are written as `*Arcs`, undirected as `*Edges`. Edges are written
+/// without weights, use [`write_pajek_custom`] to write them.
+///
+/// # Errors
+///
+/// Returns error of the writer.
+pub fn write_pajek<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+    writer: impl Write,
+) -> io::Result<()>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    write_pajek_custom(g, writer, |_| None)
+}
+
+/// Writes the [`Graph`] in Pajek `.net` format using custom function to get edge weights.
+///
+/// # Errors
+///
+/// Returns error of the writer.
+pub fn write_pajek_custom<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+    mut writer: impl Write,
+    edge_weight: impl Fn(&Edge<N, E, Ty, Ix, Dn, De>) -> Option<f64>,
+) -> io::Result<()>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    writeln!(writer, "*Vertices {}", g.node_count())?;
+    let mut number_by_idx = HashMap::with_capacity(g.node_count());
+    for (number, (idx, n)) in (1..).zip(g.nodes_iter()) {
+        let loc = n.location();
+        writeln!(
+            writer,
+            "{number} \"{}\" {} {}",
+            escape_quotes(&n.label()),
+            loc.x,
+            loc.y
+        )?;
+        number_by_idx.insert(idx, number);
+    }
+
+    writeln!(
+        writer,
+        "{}",
+        if g.is_directed() { "*Arcs" } else { "*Edges" }
+    )?;
+    for (idx, e) in g.edges_iter() {
+        let Some((start, end)) = g.edge_endpoints(idx) else {
+            continue;
+        };
+        let (start, end) = (number_by_idx[&start], number_by_idx[&end]);
+        match edge_weight(e) {
+            Some(weight) => writeln!(writer, "{start} {end} {weight}")?,
+            None => writeln!(writer, "{start} {end}")?,
+        }
+    }
+
+    Ok(())
+}
+
+fn add_vertex<Ty, Ix, Dn, De>(
+    g: &mut ImportedGraph<Ty, Ix, Dn, De>,
+    idx_by_id: &mut HashMap<usize, NodeIndex<Ix>>,
+    vertices_count: usize,
+    line: usize,
+    tokens: &[QuotedToken],
+) -> Result<(), ParseError>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    let id_token = &tokens[0];
+    let id: usize = parse_token(line, id_token.column, id_token.text, "vertex number")?;
+    if id == 0 || id > vertices_count {
+        return Err(ParseError::new(
+            line,
+            id_token.column,
+            format!("vertex number {id} is out of range 1..={vertices_count}"),
+        ));
+    }
+    if idx_by_id.contains_key(&id) {
+        return Err(ParseError::new(
+            line,
+            id_token.column,
+            format!("duplicate vertex {id}"),
+        ));
+    }
+
+    let label = tokens
+        .get(1)
+        .map_or_else(|| id.to_string(), |t| unescape_quotes(t.text));
+
+    // coordinates are optional and can be followed by other attributes
+    let location = match (tokens.get(2), tokens.get(3)) {
+        (Some(x), Some(y)) if x.text.parse::<f32>().is_ok() => Some(Pos2::new(
+            parse_token(line, x.column, x.text, "coordinate")?,
+            parse_token(line, y.column, y.text, "coordinate")?,
+        )),
+        _ => None,
+    };
+
+    let idx = add_node_imported(g, id.to_string(), label, location);
+    idx_by_id.insert(id, idx);
+
+    Ok(())
+}
+
+/// Adds vertices which are counted in the `*Vertices` header but not listed.
+fn add_undeclared_vertices<Ty, Ix, Dn, De>(
+    g: &mut ImportedGraph<Ty, Ix, Dn, De>,
+    idx_by_id: &mut HashMap<usize, NodeIndex<Ix>>,
+    vertices_count: usize,
+) where
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<String, Option<f64>, Ty, Ix>,
+    De: DisplayEdge<String, Option<f64>, Ty, Ix, Dn>,
+{
+    for id in 1..=vertices_count {
+        idx_by_id
+            .entry(id)
+            .or_insert_with(|| add_node_imported(g, id.to_string(), id.to_string(), None));
+    }
+}
+
+fn vertex<Ix: IndexType>(
+    idx_by_id: &HashMap<usize, NodeIndex<Ix>>,
+    line: usize,
+    token: &QuotedToken,
+) -> Result<NodeIndex<Ix>, ParseError> {
+    let id: usize = parse_token(line, token.column, token.text, "vertex number")?;
+    idx_by_id
+        .get(&id)
+        .copied()
+        .ok_or_else(|| ParseError::new(line, token.column, format!("unknown vertex {id}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{Directed, Undirected};
+
+    use super::*;
+    use crate::{DefaultEdgeShape, DefaultNodeShape};
+
+    const NET: &str = r#"% karate subset
+*Vertices 4
+1 "Mr Hi" 0.25 0.5 0.5 ic Red
+2 "John A"
+4 Dave
+*Arcs
+1 2 1.5
+*Edges
+2 4
+*Arcslist
+1 3 4
+"#;
+
+    #[test]
+    fn test_graph_from_pajek() {
+        let g: ImportedGraph<Directed> = graph_from_pajek(NET.as_bytes()).unwrap();
+
+        let nodes = g
+            .nodes_iter()
+            .map(|(_, n)| (n.payload().as_str(), n.label()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            vec![
+                ("1", "Mr Hi".to_string()),
+                ("2", "John A".to_string()),
+                ("4", "Dave".to_string()),
+                ("3", "3".to_string())
+            ]
+        );
+        assert_eq!(
+            g.node(NodeIndex::new(0)).unwrap().location(),
+            Pos2::new(0.25, 0.5)
+        );
+
+        let weights = g
+            .edges_iter()
+            .map(|(_, e)| *e.payload())
+            .collect::<Vec<_>>();
+        assert_eq!(weights, vec![Some(1.5), None, None, None]);
+    }
+
+    #[test]
+    fn test_graph_from_pajek_errors() {
+        let parse = |net: &str| {
+            graph_from_pajek::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>(net.as_bytes())
+                .err()
+                .unwrap()
+        };
+
+        let err = parse("*Vertices 2\n3 \"c\"\n");
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = parse("*Vertices 2\n*Edges\n1 5\n");
+        assert_eq!((err.line, err.column), (3, 3));
+
+        let err = parse("*Vertices 2\n*Edges\n1 2 heavy\n");
+        assert_eq!((err.line, err.column), (3, 5));
+
+        let err = parse("*Vertices 5000000000\n");
+        assert_eq!((err.line, err.column), (1, 11));
+
+        let err = graph_from_pajek::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(
+            "*Vertices 300\n".as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.line, err.column), (1, 11));
+
+        let net = format!("*Vertices 2\n*Edges\n{}", "1 2\n".repeat(300));
+        let err =
+            graph_from_pajek::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>(net.as_bytes())
+                .err()
+                .unwrap();
+        assert_eq!(err.line, 258);
+    }
+
+    #[test]
+    fn test_write_pajek_roundtrip() {
+        let g: ImportedGraph<Undirected> = graph_from_pajek(NET.as_bytes()).unwrap();
+
+        let mut out = vec![];
+        write_pajek_custom(&g, &mut out, |e| *e.payload()).unwrap();
+        let restored: ImportedGraph<Undirected> = graph_from_pajek(out.as_slice()).unwrap();
+
+        assert_eq!(restored.node_count(), 4);
+        for ((_, a), (_, b)) in g.nodes_iter().zip(restored.nodes_iter()) {
+            assert_eq!(a.label(), b.label());
+            assert_eq!(a.location(), b.location());
+        }
+        let weights = restored
+            .edges_iter()
+            .map(|(_, e)| *e.payload())
+            .collect::<Vec<_>>();
+        assert_eq!(weights, vec![Some(1.5), None, None, None]);
+    }
+}
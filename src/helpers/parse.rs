@@ -0,0 +1,214 @@
+use std::fmt::Display;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use petgraph::stable_graph::IndexType;
+
+/// Error returned by the graph importers.
+///
+/// Lines and columns are 1-based and point to the place in the input where the problem was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Iterates over lines of the reader together with their 1-based numbers.
+pub(crate) fn numbered_lines(
+    reader: impl BufRead,
+) -> impl Iterator<Item = Result<(usize, String), ParseError>> {
+    reader.lines().enumerate().map(|(i, line)| {
+        line.map(|l| (i + 1, l))
+            .map_err(|err| ParseError::new(i + 1, 1, err.to_string()))
+    })
+}
+
+/// Splits the line into tokens separated by whitespace or commas.
+/// Returns tokens together with their 1-based columns.
+pub(crate) fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut res = vec![];
+    let mut start = None;
+    let mut column = 0;
+    for (byte_idx, c) in line.char_indices() {
+        column += 1;
+        let is_separator = c.is_whitespace() || c == ',';
+        match (start, is_separator) {
+            (None, false) => start = Some((column, byte_idx)),
+            (Some((token_column, token_start)), true) => {
+                res.push((token_column, &line[token_start..byte_idx]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((token_column, token_start)) = start {
+        res.push((token_column, &line[token_start..]));
+    }
+
+    res
+}
+
+/// Token of a line where text in double quotes forms a single token.
+pub(crate) struct QuotedToken<'a> {
+    pub column: usize,
+    /// Token text without the quotes.
+    pub text: &'a str,
+    pub quoted: bool,
+}
+
+/// Splits the line into tokens separated by whitespace. Text in double quotes is a single token
+/// and every char from `delimiters` is a token on its own.
+pub(crate) fn quoted_tokens<'a>(
+    line: &'a str,
+    line_no: usize,
+    delimiters: &[char],
+) -> Result<Vec<QuotedToken<'a>>, ParseError> {
+    let mut res = vec![];
+    let mut chars = line.char_indices().zip(1..).peekable();
+    while let Some(((byte_idx, c), column)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if delimiters.contains(&c) {
+            res.push(QuotedToken {
+                column,
+                text: &line[byte_idx..byte_idx + c.len_utf8()],
+                quoted: false,
+            });
+            continue;
+        }
+
+        if c == '"' {
+            let start = byte_idx + 1;
+            let Some(((end, _), _)) = chars.by_ref().find(|((_, c), _)| *c == '"') else {
+                return Err(ParseError::new(line_no, column, "unterminated string"));
+            };
+            res.push(QuotedToken {
+                column,
+                text: &line[start..end],
+                quoted: true,
+            });
+            continue;
+        }
+
+        let mut end = line.len();
+        while let Some(((next_idx, next), _)) = chars.peek() {
+            if next.is_whitespace() || *next == '"' || delimiters.contains(next) {
+                end = *next_idx;
+                break;
+            }
+            chars.next();
+        }
+        res.push(QuotedToken {
+            column,
+            text: &line[byte_idx..end],
+            quoted: false,
+        });
+    }
+
+    Ok(res)
+}
+
+/// Escapes double quotes in the text written to the formats which do not support escaping.
+pub(crate) fn escape_quotes(text: &str) -> String {
+    text.replace('"', "&quot;")
+}
+
+/// Reverts [`escape_quotes`].
+pub(crate) fn unescape_quotes(text: &str) -> String {
+    text.replace("&quot;", "\"")
+}
+
+/// Parses the token reporting its position on failure.
+pub(crate) fn parse_token<T: FromStr>(
+    line: usize,
+    column: usize,
+    token: &str,
+    what: &str,
+) -> Result<T, ParseError> {
+    token
+        .trim()
+        .parse()
+        .map_err(|_| ParseError::new(line, column, format!("invalid {what} `{token}`")))
+}
+
+/// Checks that `count` elements fit into the index type of the graph. `None` means the count
+/// has overflowed. The max value of the index type is reserved by petgraph.
+pub(crate) fn check_count<Ix: IndexType>(
+    line: usize,
+    column: usize,
+    count: Option<usize>,
+    what: &str,
+) -> Result<usize, ParseError> {
+    let max = <Ix as IndexType>::max().index();
+    match count {
+        Some(count) if count <= max => Ok(count),
+        _ => Err(ParseError::new(
+            line,
+            column,
+            format!("too many {what}, the graph index type supports at most {max}"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            tokens("  1 2,3\t 45"),
+            vec![(3, "1"), (5, "2"), (7, "3"), (10, "45")]
+        );
+        assert!(tokens("   ").is_empty());
+    }
+
+    #[test]
+    fn test_quoted_tokens() {
+        let res = quoted_tokens(r#"node[id 1 label "a b"]"#, 1, &['[', ']']).unwrap();
+        let res = res
+            .iter()
+            .map(|t| (t.column, t.text, t.quoted))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            res,
+            vec![
+                (1, "node", false),
+                (5, "[", false),
+                (6, "id", false),
+                (9, "1", false),
+                (11, "label", false),
+                (17, "a b", true),
+                (22, "]", false),
+            ]
+        );
+
+        let err = quoted_tokens(r#"1 "a"#, 4, &[]).err().unwrap();
+        assert_eq!((err.line, err.column), (4, 3));
+    }
+}
@@ -0,0 +1,470 @@
+use egui::Pos2;
+use petgraph::{
+    stable_graph::{EdgeIndex, IndexType, NodeIndex},
+    EdgeType,
+};
+
+use crate::{DisplayEdge, DisplayNode, Edge, EdgeProps, Graph, Node, NodeProps};
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Changes which revert a single user action.
+pub(crate) type Step<N, E, Ty, Ix, Dn, De> = Vec<Change<N, E, Ty, Ix, Dn, De>>;
+
+/// The history references indices which are taken by other elements, e.g. after changes made
+/// directly to [`Graph::g`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OutOfSync;
+
+/// Reversible change of the graph. Applying a change returns the change which reverts it.
+#[derive(Debug, Clone)]
+pub(crate) enum Change<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    AddNode(NodeIndex<Ix>, Node<N, E, Ty, Ix, Dn>),
+    RemoveNode(NodeIndex<Ix>),
+    AddEdge(
+        EdgeIndex<Ix>,
+        NodeIndex<Ix>,
+        NodeIndex<Ix>,
+        Edge<N, E, Ty, Ix, Dn, De>,
+    ),
+    RemoveEdge(EdgeIndex<Ix>),
+    ReconnectEdge(EdgeIndex<Ix>, NodeIndex<Ix>, NodeIndex<Ix>),
+    NodeProps(NodeIndex<Ix>, NodeProps<N>),
+    EdgeProps(EdgeIndex<Ix>, EdgeProps<E>),
+    Location(NodeIndex<Ix>, Option<Pos2>),
+}
+
+impl<N, E, Ty, Ix, Dn, De> Change<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    /// Applies the change to the graph and returns the change which reverts it.
+    /// Returns None if the change references elements which do not exist.
+    ///
+    /// Removed nodes and edges are restored with their indices because [`petgraph::stable_graph::StableGraph`]
+    /// reuses the last freed index, which holds as long as the changes are applied in the reverse order.
+    /// If the index is taken by another element, the element is not restored and [`OutOfSync`]
+    /// is returned.
+    fn apply(self, g: &mut Graph<N, E, Ty, Ix, Dn, De>) -> Result<Option<Self>, OutOfSync> {
+        let revert = match self {
+            Change::AddNode(idx, node) => {
+                let new_idx = g.g.add_node(node);
+                g.mark_node_dirty(new_idx);
+                if new_idx != idx {
+                    g.g.remove_node(new_idx);
+                    return Err(OutOfSync);
+                }
+                Change::RemoveNode(new_idx)
+            }
+            Change::RemoveNode(idx) => {
+                g.mark_node_dirty(idx);
+                match g.g.remove_node(idx) {
+                    Some(node) => Change::AddNode(idx, node),
+                    None => return Ok(None),
+                }
+            }
+            Change::AddEdge(idx, start, end, mut edge) => {
+                if !g.g.contains_node(start) || !g.g.contains_node(end) {
+                    return Err(OutOfSync);
+                }
+                edge.set_order(g.g.edges_connecting(start, end).count());
+                let new_idx = g.g.add_edge(start, end, edge);
+                g.mark_edge_dirty(new_idx);
+                if new_idx != idx {
+                    g.g.remove_edge(new_idx);
+                    return Err(OutOfSync);
+                }
+                Change::RemoveEdge(new_idx)
+            }
+            Change::RemoveEdge(idx) => {
+                let Some((start, end)) = g.edge_endpoints(idx) else {
+                    return Ok(None);
+                };
+                match g.remove_edge(idx) {
+                    Some(edge) => Change::AddEdge(idx, start, end, edge),
+                    None => return Ok(None),
+                }
+            }
+            Change::ReconnectEdge(idx, start, end) => {
+                let Some((old_start, old_end)) = g.edge_endpoints(idx) else {
+                    return Ok(None);
+                };
+                let Some(new_idx) = g.reconnect_edge(idx, start, end) else {
+                    return Ok(None);
+                };
+                Change::ReconnectEdge(new_idx, old_start, old_end)
+            }
+            Change::NodeProps(idx, mut props) => {
+                let Some(n) = g.node_mut(idx) else {
+                    return Ok(None);
+                };
+                std::mem::swap(n.props_mut(), &mut props);
+                Change::NodeProps(idx, props)
+            }
+            Change::EdgeProps(idx, mut props) => {
+                let Some(e) = g.edge_mut(idx) else {
+                    return Ok(None);
+                };
+                std::mem::swap(e.props_mut(), &mut props);
+                Change::EdgeProps(idx, props)
+            }
+            Change::Location(idx, mut location) => {
+                let Some(n) = g.node_mut(idx) else {
+                    return Ok(None);
+                };
+                std::mem::swap(&mut n.props_mut().location_user, &mut location);
+                Change::Location(idx, location)
+            }
+        };
+
+        Ok(Some(revert))
+    }
+}
+
+/// Undo and redo stacks of the graph changes.
+///
+/// Every step is a list of changes which revert a user action. Changes recorded between
+/// [`History::begin_group`] and [`History::end_group`] form a single step.
+#[derive(Debug, Clone)]
+pub(crate) struct History<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    enabled: bool,
+    limit: usize,
+    undo: Vec<Step<N, E, Ty, Ix, Dn, De>>,
+    redo: Vec<Step<N, E, Ty, Ix, Dn, De>>,
+    group: Step<N, E, Ty, Ix, Dn, De>,
+    group_depth: usize,
+
+    /// Locations of the dragged nodes at the start of the drag
+    drag_start: Vec<(NodeIndex<Ix>, Option<Pos2>)>,
+}
+
+impl<N, E, Ty, Ix, Dn, De> Default for History<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    fn default() -> Self {
+        Self {
+            enabled: bool::default(),
+            limit: DEFAULT_LIMIT,
+            undo: Vec::default(),
+            redo: Vec::default(),
+            group: Vec::default(),
+            group_depth: usize::default(),
+            drag_start: Vec::default(),
+        }
+    }
+}
+
+impl<N, E, Ty, Ix, Dn, De> History<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.truncate();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group.clear();
+        self.group_depth = 0;
+        self.drag_start.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Records the change which reverts the action. The change is created only if the history is enabled.
+    pub fn record(&mut self, revert: impl FnOnce() -> Change<N, E, Ty, Ix, Dn, De>) {
+        if !self.enabled {
+            return;
+        }
+
+        self.redo.clear();
+        self.group.push(revert());
+        if self.group_depth == 0 {
+            self.end_group();
+        }
+    }
+
+    /// Starts a group of changes. Groups can be nested, the outermost one forms the step.
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth > 0 || self.group.is_empty() {
+            return;
+        }
+
+        self.undo.push(std::mem::take(&mut self.group));
+        self.truncate();
+    }
+
+    /// Remembers locations of the nodes at the start of the drag.
+    pub fn begin_drag(&mut self, nodes: impl Iterator<Item = (NodeIndex<Ix>, Option<Pos2>)>) {
+        if self.enabled {
+            self.drag_start = nodes.collect();
+        }
+    }
+
+    /// Returns locations of the nodes at the start of the drag.
+    pub fn end_drag(&mut self) -> Vec<(NodeIndex<Ix>, Option<Pos2>)> {
+        std::mem::take(&mut self.drag_start)
+    }
+
+    /// Returns the step which reverts the last redone or recorded changes.
+    #[cfg(feature = "events")]
+    pub fn last_undo(&self) -> Option<&Step<N, E, Ty, Ix, Dn, De>> {
+        self.undo.last()
+    }
+
+    /// Returns the step which reverts the last undone changes.
+    #[cfg(feature = "events")]
+    pub fn last_redo(&self) -> Option<&Step<N, E, Ty, Ix, Dn, De>> {
+        self.redo.last()
+    }
+
+    pub fn pop_undo(&mut self) -> Option<Step<N, E, Ty, Ix, Dn, De>> {
+        self.undo.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<Step<N, E, Ty, Ix, Dn, De>> {
+        self.redo.pop()
+    }
+
+    pub fn push_undo(&mut self, step: Step<N, E, Ty, Ix, Dn, De>) {
+        self.undo.push(step);
+        self.truncate();
+    }
+
+    pub fn push_redo(&mut self, step: Step<N, E, Ty, Ix, Dn, De>) {
+        self.redo.push(step);
+    }
+
+    fn truncate(&mut self) {
+        if self.undo.len() > self.limit {
+            let excess = self.undo.len() - self.limit;
+            self.undo.drain(..excess);
+        }
+    }
+}
+
+/// Applies the step in the reverse order and returns the step which reverts it.
+///
+/// Returns None if the history is out of sync with the graph. The changes of the step which were
+/// already applied are reverted then, so the graph is left as it was.
+pub(crate) fn apply_step<N, E, Ty, Ix, Dn, De>(
+    g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    step: Step<N, E, Ty, Ix, Dn, De>,
+) -> Option<Step<N, E, Ty, Ix, Dn, De>>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let mut revert = Vec::with_capacity(step.len());
+    for change in step.into_iter().rev() {
+        match change.apply(g) {
+            Ok(Some(change)) => revert.push(change),
+            Ok(None) => {}
+            Err(OutOfSync) => {
+                for change in revert.into_iter().rev() {
+                    let _ = change.apply(g);
+                }
+                return None;
+            }
+        }
+    }
+
+    Some(revert)
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_undo_redo() {
+        let mut g: Graph<&str, &str> = Graph::new(StableGraph::default());
+        g.set_history_enabled(true);
+
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let ab = g.add_edge(a, b, "ab");
+        g.set_node_label(b, "renamed".to_string());
+        g.remove_node(a);
+        assert_eq!((g.node_count(), g.edge_count()), (1, 0));
+
+        assert!(g.undo());
+        assert_eq!((g.node_count(), g.edge_count()), (2, 1));
+        assert_eq!(g.edge_endpoints(ab), Some((a, b)));
+        assert_eq!(*g.node(a).unwrap().payload(), "a");
+
+        assert!(g.undo());
+        assert_eq!(g.node(b).unwrap().label(), "1");
+
+        assert!(g.redo());
+        assert!(g.redo());
+        assert!(!g.redo());
+        assert_eq!(g.node(b).unwrap().label(), "renamed");
+        assert!(g.node(a).is_none());
+
+        while g.undo() {}
+        assert_eq!(g.node_count(), 0);
+    }
+
+    #[test]
+    fn test_undo_out_of_sync() {
+        let mut g: Graph<&str, &str> = Graph::new(StableGraph::default());
+        g.set_history_enabled(true);
+
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        g.add_edge(a, b, "ab");
+        g.remove_node(b);
+
+        // the freed node index is taken outside of the history
+        let c = g.g.add_node(Node::new("c"));
+        assert_eq!(c, b);
+        assert!(!g.undo());
+        assert!(!g.can_undo() && !g.can_redo());
+        assert_eq!((g.node_count(), g.edge_count()), (2, 0));
+        assert_eq!(*g.node(b).unwrap().payload(), "c");
+    }
+
+    #[test]
+    fn test_undo_out_of_sync_rolls_back_step() {
+        let mut g: Graph<&str, &str> = Graph::new(StableGraph::default());
+        g.set_history_enabled(true);
+
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let ab = g.add_edge(a, b, "ab");
+        g.remove_node(a);
+
+        // the node index is still free but the edge index is taken
+        let bb = g.g.add_edge(b, b, Edge::new("bb"));
+        assert_eq!(bb, ab);
+        assert!(!g.undo());
+        assert!(!g.can_undo());
+        assert!(g.node(a).is_none());
+        assert_eq!((g.node_count(), g.edge_count()), (1, 1));
+        assert_eq!(g.edge_endpoints(bb), Some((b, b)));
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut g: Graph<&str, &str> = Graph::new(StableGraph::default());
+        g.set_history_enabled(true);
+        g.set_history_limit(3);
+
+        for _ in 0..5 {
+            g.add_node("n");
+        }
+
+        let mut steps = 0;
+        while g.undo() {
+            steps += 1;
+        }
+        assert_eq!(steps, 3);
+        assert_eq!(g.node_count(), 2);
+
+        g.set_history_limit(1);
+        assert!(g.redo());
+        assert!(g.redo());
+        assert!(g.undo());
+        assert!(!g.undo());
+    }
+
+    #[test]
+    fn test_history_group() {
+        let mut g: Graph<&str, &str> = Graph::new(StableGraph::default());
+        g.set_history_enabled(true);
+
+        g.history.begin_group();
+        let a = g.add_node("a");
+        g.history.begin_group();
+        let b = g.add_node("b");
+        g.add_edge(a, b, "ab");
+        g.history.end_group();
+        g.set_node_label(a, "renamed".to_string());
+        g.history.end_group();
+        g.add_node("c");
+
+        assert!(g.undo());
+        assert_eq!(g.node_count(), 2);
+        assert!(g.undo());
+        assert_eq!((g.node_count(), g.edge_count()), (0, 0));
+        assert!(!g.undo());
+
+        assert!(g.redo());
+        assert_eq!((g.node_count(), g.edge_count()), (2, 1));
+        assert_eq!(g.node(a).unwrap().label(), "renamed");
+
+        // disabling the history in the middle of a group drops it
+        g.history.begin_group();
+        g.add_node("d");
+        g.set_history_enabled(false);
+        g.set_history_enabled(true);
+        g.history.end_group();
+        g.add_node("e");
+        assert!(g.undo());
+        assert!(!g.undo());
+        assert_eq!(g.node_count(), 3);
+    }
+}
@@ -22,7 +22,11 @@ pub struct State {
     triggered: bool,
 }
 
-impl LayoutState for State {}
+impl LayoutState for State {
+    fn is_settled(&self) -> bool {
+        self.triggered
+    }
+}
 
 #[derive(Debug, Default)]
 pub struct Hierarchical {
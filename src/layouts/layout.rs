@@ -3,7 +3,15 @@ use petgraph::{stable_graph::IndexType, EdgeType};
 
 use crate::{DisplayEdge, DisplayNode, Graph};
 
-pub trait LayoutState: SerializableAny + Default {}
+pub trait LayoutState: SerializableAny + Default {
+    /// Whether the layout has finished moving the nodes. [`crate::GraphView`] keeps requesting
+    /// repaints while the layout is not settled and stays idle otherwise.
+    ///
+    /// Default: `false`, the widget is repainted on every frame.
+    fn is_settled(&self) -> bool {
+        false
+    }
+}
 
 pub trait Layout<S>: Default
 where
@@ -14,7 +14,11 @@ pub struct State {
     triggered: bool,
 }
 
-impl LayoutState for State {}
+impl LayoutState for State {
+    fn is_settled(&self) -> bool {
+        self.triggered
+    }
+}
 
 /// Randomly places nodes on the canvas. Does not override existing locations. Applies once.
 #[derive(Debug, Default)]
@@ -1,26 +1,46 @@
+mod camera;
+mod clipboard;
 mod draw;
 mod elements;
 mod graph;
 mod graph_view;
 mod helpers;
+mod history;
 mod layouts;
 mod metadata;
+mod minimap;
+mod selection;
+mod session;
 mod settings;
+mod spatial;
 
-pub use draw::{DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, DrawContext};
-pub use elements::{Edge, EdgeProps, Node, NodeProps};
+pub use camera::Camera;
+pub use clipboard::Subgraph;
+pub use draw::{
+    DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, DrawContext, LevelOfDetail,
+};
+pub use elements::{Edge, EdgeProps, Highlight, Node, NodeProps};
 pub use graph::Graph;
-pub use graph_view::{DefaultGraphView, GraphView};
+pub use graph_view::{ContextMenuTarget, DefaultGraphView, GraphView};
 pub use helpers::{
     add_edge, add_edge_custom, add_node, add_node_custom, default_edge_transform,
-    default_node_transform, node_size, random_graph, to_graph, to_graph_custom,
+    default_node_transform, edge_list_from_csv, from_matrix_graph, graph_from_dense_matrix,
+    graph_from_gml, graph_from_mermaid, graph_from_pajek, graph_from_sparse_matrix,
+    graph_to_mermaid, graph_to_mermaid_custom, node_attributes_from_csv, node_size, random_graph,
+    to_graph, to_graph_custom, write_gml, write_gml_custom, write_pajek, write_pajek_custom,
+    CsvColumn, CsvEdgeListOptions, CsvNodeAttributesOptions, ImportedGraph, MermaidDirection,
+    MermaidExportOptions, MermaidShape, ParseError,
 };
 pub use layouts::hierarchical::{
     Hierarchical as LayoutHierarchical, State as LayoutStateHierarchical,
 };
 pub use layouts::random::{Random as LayoutRandom, State as LayoutStateRandom};
 pub use metadata::Metadata;
-pub use settings::{SettingsInteraction, SettingsNavigation, SettingsStyle};
+pub use session::{Session, SessionCamera, SessionEdge, SessionError, SessionNode};
+pub use settings::{HighlightDirection, SettingsInteraction, SettingsNavigation, SettingsStyle};
 
 #[cfg(feature = "events")]
 pub mod events;
+
+#[cfg(feature = "headless")]
+pub mod headless;
@@ -2,7 +2,7 @@ use egui::{Id, Pos2, Rect, Vec2};
 use petgraph::{stable_graph::IndexType, EdgeType};
 use serde::{Deserialize, Serialize};
 
-use crate::{node_size, DisplayNode, Node};
+use crate::{camera::CameraAnimation, node_size, selection::SelectionArea, DisplayNode, Node};
 
 const KEY: &str = "egui_graphs_metadata";
 
@@ -49,6 +49,42 @@ impl Bounds {
     }
 }
 
+/// Edge which is being drawn by the user from the anchor node to the pointer.
+#[derive(Clone, Debug)]
+pub(crate) struct EdgeDraft {
+    /// Node which stays connected to the edge
+    pub anchor: usize,
+    /// Pointer position in the canvas coordinates
+    pub end: Pos2,
+    /// Reconnected edge and whether its start follows the pointer
+    pub reconnect: Option<(usize, bool)>,
+}
+
+/// Element which label is edited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LabelTarget {
+    Node(usize),
+    Edge(usize),
+}
+
+/// Label which is being edited by the user in the text field over the graph.
+#[derive(Clone, Debug)]
+pub(crate) struct LabelEdit {
+    pub target: LabelTarget,
+    pub text: String,
+    /// Whether the text field has already received focus
+    pub focused: bool,
+}
+
+/// Element for which the context menu is opened.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum MenuTarget {
+    Node(usize),
+    Edge(usize),
+    /// Position in the canvas coordinates
+    Canvas(Pos2),
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Metadata {
     /// Whether the frame is the first one
@@ -62,6 +98,26 @@ pub struct Metadata {
 
     /// State of bounds iteration
     bounds: Bounds,
+
+    /// Selection area which is being drawn by the user
+    #[serde(skip)]
+    pub(crate) selection: Option<SelectionArea>,
+
+    /// Edge which is being drawn by the user
+    #[serde(skip)]
+    pub(crate) edge_draft: Option<EdgeDraft>,
+
+    /// Label which is being edited
+    #[serde(skip)]
+    pub(crate) label_edit: Option<LabelEdit>,
+
+    /// Element for which the context menu is opened
+    #[serde(skip)]
+    pub(crate) context_menu: Option<MenuTarget>,
+
+    /// Camera transition requested by the application
+    #[serde(skip)]
+    pub(crate) camera_animation: Option<CameraAnimation>,
 }
 
 impl Default for Metadata {
@@ -72,13 +128,22 @@ impl Default for Metadata {
             pan: Vec2::default(),
             top_left: Pos2::default(),
             bounds: Bounds::default(),
+            selection: Option::default(),
+            edge_draft: Option::default(),
+            label_edit: Option::default(),
+            context_menu: Option::default(),
+            camera_animation: Option::default(),
         }
     }
 }
 
 impl Metadata {
     pub fn load(ui: &egui::Ui) -> Self {
-        ui.data_mut(|data| {
+        Self::load_from_ctx(ui.ctx())
+    }
+
+    pub(crate) fn load_from_ctx(ctx: &egui::Context) -> Self {
+        ctx.data_mut(|data| {
             data.get_persisted::<Metadata>(Id::new(KEY))
                 .unwrap_or_default()
         })
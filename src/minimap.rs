@@ -0,0 +1,137 @@
+use egui::{Painter, Pos2, Rect, Shape, Stroke, Ui, Vec2};
+use petgraph::{stable_graph::IndexType, EdgeType};
+
+use crate::{DisplayEdge, DisplayNode, Graph};
+
+/// Distance in screen pixels between the minimap and the corner of the widget.
+const MARGIN: f32 = 10.;
+
+/// Radius of the nodes on the minimap in screen pixels.
+const NODE_RADIUS: f32 = 1.5;
+
+/// Maps the graph bounds to the minimap placed in the bottom right corner of the view.
+#[derive(Debug, Clone)]
+pub(crate) struct Minimap {
+    pub rect: Rect,
+    bounds: Rect,
+    scale: f32,
+    offset: Vec2,
+}
+
+impl Minimap {
+    /// Creates the minimap of the given size which fits the bounds in canvas coordinates.
+    pub fn new(view: Rect, bounds: Rect, size: Vec2) -> Self {
+        let size = size
+            .min(view.size() - Vec2::splat(2. * MARGIN))
+            .max(Vec2::ZERO);
+        let rect = Rect::from_min_size(view.max - size - Vec2::splat(MARGIN), size);
+
+        let bounds = bounds.expand(bounds.size().max_elem().max(1.) * 0.05);
+        let scale = (size.x / bounds.width()).min(size.y / bounds.height());
+        // center the bounds in the minimap
+        let offset = rect.center().to_vec2() - bounds.center().to_vec2() * scale;
+
+        Self {
+            rect,
+            bounds,
+            scale,
+            offset,
+        }
+    }
+
+    pub fn canvas_to_minimap(&self, pos: Pos2) -> Pos2 {
+        (pos.to_vec2() * self.scale + self.offset).to_pos2()
+    }
+
+    pub fn minimap_to_canvas(&self, pos: Pos2) -> Pos2 {
+        ((pos.to_vec2() - self.offset) / self.scale).to_pos2()
+    }
+
+    /// Draws the simplified graph and the viewport given in canvas coordinates.
+    pub fn draw<N, E, Ty, Ix, Dn, De>(
+        &self,
+        ui: &Ui,
+        painter: &Painter,
+        g: &Graph<N, E, Ty, Ix, Dn, De>,
+        viewport: Rect,
+    ) where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        let visuals = ui.visuals();
+        let painter = painter.with_clip_rect(self.rect);
+        painter.rect(
+            self.rect,
+            visuals.window_rounding,
+            visuals.extreme_bg_color,
+            visuals.window_stroke,
+        );
+
+        let edge_stroke = Stroke::new(1., visuals.widgets.inactive.fg_stroke.color);
+        let mut shapes = Vec::with_capacity(g.edge_count() + g.node_count());
+        for (idx, _) in g.edges_iter() {
+            let Some((start, end)) = g.edge_endpoints(idx) else {
+                continue;
+            };
+            let (Some(start), Some(end)) = (g.node(start), g.node(end)) else {
+                continue;
+            };
+            shapes.push(Shape::line_segment(
+                [
+                    self.canvas_to_minimap(start.location()),
+                    self.canvas_to_minimap(end.location()),
+                ],
+                edge_stroke,
+            ));
+        }
+        for (_, n) in g.nodes_iter() {
+            let color = n
+                .color()
+                .unwrap_or(visuals.widgets.inactive.fg_stroke.color);
+            shapes.push(Shape::circle_filled(
+                self.canvas_to_minimap(n.location()),
+                NODE_RADIUS,
+                color,
+            ));
+        }
+        painter.extend(shapes);
+
+        let viewport = Rect::from_min_max(
+            self.canvas_to_minimap(viewport.min),
+            self.canvas_to_minimap(viewport.max),
+        );
+        painter.rect_stroke(viewport, 0., visuals.selection.stroke);
+    }
+
+    /// Whether the minimap has space to show the bounds.
+    pub fn is_valid(&self) -> bool {
+        self.scale.is_finite() && self.scale > 0. && self.bounds.is_positive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform() {
+        let view = Rect::from_min_size(Pos2::ZERO, Vec2::new(1000., 800.));
+        let bounds = Rect::from_min_size(Pos2::new(-100., -100.), Vec2::splat(200.));
+        let minimap = Minimap::new(view, bounds, Vec2::new(200., 100.));
+
+        assert_eq!(minimap.rect.max, Pos2::new(990., 790.));
+        assert!(minimap.is_valid());
+        assert_eq!(minimap.canvas_to_minimap(Pos2::ZERO), minimap.rect.center());
+
+        let pos = Pos2::new(30., -40.);
+        let back = minimap.minimap_to_canvas(minimap.canvas_to_minimap(pos));
+        assert!((back - pos).length() < 1e-3);
+        assert!(minimap
+            .rect
+            .contains(minimap.canvas_to_minimap(bounds.left_top())));
+    }
+}
@@ -0,0 +1,167 @@
+use egui::{Pos2, Rect};
+use petgraph::{stable_graph::IndexType, EdgeType};
+
+use crate::{DisplayNode, Node};
+
+/// How the nodes and edges inside of the selection area change the current selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SelectionMode {
+    /// Selection is replaced by the elements inside of the area.
+    Replace,
+    /// Elements inside of the area are added to the selection.
+    Add,
+    /// Elements inside of the area are removed from the selection.
+    Remove,
+}
+
+/// Shape of the selection area.
+#[derive(Debug, Clone)]
+pub(crate) enum SelectionShape {
+    Rect {
+        start: Pos2,
+        end: Pos2,
+    },
+    /// Free-form polygon recorded from the pointer path.
+    Lasso(Vec<Pos2>),
+}
+
+/// Area which is being drawn by the user to select elements. Coordinates are in the canvas space
+/// so the area stays in place when the graph is zoomed or panned.
+#[derive(Debug, Clone)]
+pub(crate) struct SelectionArea {
+    pub mode: SelectionMode,
+    pub shape: SelectionShape,
+}
+
+impl SelectionArea {
+    pub fn new_rect(mode: SelectionMode, start: Pos2) -> Self {
+        Self {
+            mode,
+            shape: SelectionShape::Rect { start, end: start },
+        }
+    }
+
+    pub fn new_lasso(mode: SelectionMode, start: Pos2) -> Self {
+        Self {
+            mode,
+            shape: SelectionShape::Lasso(vec![start]),
+        }
+    }
+
+    /// Extends the area to the new pointer position.
+    pub fn extend(&mut self, pos: Pos2) {
+        match &mut self.shape {
+            SelectionShape::Rect { end, .. } => *end = pos,
+            SelectionShape::Lasso(path) => {
+                if path.last() != Some(&pos) {
+                    path.push(pos);
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, pos: Pos2) -> bool {
+        match &self.shape {
+            SelectionShape::Rect { start, end } => Rect::from_two_pos(*start, *end).contains(pos),
+            SelectionShape::Lasso(path) => is_inside_polygon(path, pos),
+        }
+    }
+
+    /// Bounding rect of the area.
+    pub fn bounds(&self) -> Rect {
+        match &self.shape {
+            SelectionShape::Rect { start, end } => Rect::from_two_pos(*start, *end),
+            SelectionShape::Lasso(path) => Rect::from_points(path),
+        }
+    }
+
+    /// Checks if the node shape intersects the area.
+    pub fn intersects_node<N, E, Ty, Ix, D>(&self, n: &Node<N, E, Ty, Ix, D>) -> bool
+    where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        D: DisplayNode<N, E, Ty, Ix>,
+    {
+        let center = n.location();
+        if self.contains(center) {
+            return true;
+        }
+
+        match &self.shape {
+            // the closest to the node center point of the rect is inside of the node if they intersect
+            SelectionShape::Rect { start, end } => n
+                .display()
+                .is_inside(Rect::from_two_pos(*start, *end).clamp(center)),
+            SelectionShape::Lasso(path) => path.iter().any(|p| n.display().is_inside(*p)),
+        }
+    }
+}
+
+/// Even-odd rule check of the point against the closed polygon.
+fn is_inside_polygon(polygon: &[Pos2], pos: Pos2) -> bool {
+    let mut inside = false;
+    let mut prev = match polygon.last() {
+        Some(p) => *p,
+        None => return false,
+    };
+    for curr in polygon {
+        if (curr.y > pos.y) != (prev.y > pos.y) {
+            let x = curr.x + (pos.y - curr.y) * (prev.x - curr.x) / (prev.y - curr.y);
+            if pos.x < x {
+                inside = !inside;
+            }
+        }
+        prev = *curr;
+    }
+
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::Directed;
+
+    use super::*;
+    use crate::DefaultNodeShape;
+
+    #[test]
+    fn test_intersects_node() {
+        let mut props = Node::<(), (), Directed, u32, DefaultNodeShape>::new(())
+            .props()
+            .clone();
+        props.location = Pos2::new(10., 10.);
+        let n = Node::<(), (), Directed, u32, DefaultNodeShape>::new_with_props(props);
+
+        let mut area = SelectionArea::new_rect(SelectionMode::Replace, Pos2::new(12., 12.));
+        area.extend(Pos2::new(30., 30.));
+        assert!(area.intersects_node(&n));
+
+        let mut area = SelectionArea::new_rect(SelectionMode::Replace, Pos2::new(20., 20.));
+        area.extend(Pos2::new(30., 30.));
+        assert!(!area.intersects_node(&n));
+    }
+
+    #[test]
+    fn test_lasso_contains() {
+        // U-shaped lasso around (0, 0) with the notch at (5, 5)
+        let mut area = SelectionArea::new_lasso(SelectionMode::Add, Pos2::new(-10., -10.));
+        for p in [
+            (20., -10.),
+            (20., 20.),
+            (10., 20.),
+            (10., 0.),
+            (0., 0.),
+            (0., 20.),
+        ] {
+            area.extend(Pos2::new(p.0, p.1));
+        }
+        area.extend(Pos2::new(-10., 20.));
+
+        assert!(area.contains(Pos2::new(-5., 5.)));
+        assert!(area.contains(Pos2::new(15., 5.)));
+        assert!(!area.contains(Pos2::new(5., 10.)));
+        assert!(!area.contains(Pos2::new(30., 5.)));
+    }
+}
@@ -0,0 +1,512 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use egui::{Color32, Pos2, Vec2};
+use petgraph::{
+    graph::IndexType,
+    stable_graph::{EdgeIndex, NodeIndex, StableGraph},
+    visit::{EdgeIndexable, EdgeRef, NodeIndexable},
+    EdgeType,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{DisplayEdge, DisplayNode, Edge, Graph, Highlight, Metadata, Node, NodeProps};
+
+/// Max number of the placeholders which fill holes in the node or edge indices of a session.
+/// Sessions with larger gaps are rejected to not allocate placeholders for corrupted indices.
+const MAX_INDEX_GAP: usize = 1 << 20;
+
+/// Error which can occur while restoring a [`Graph`] from a [`Session`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    /// Session was saved from a graph with a different edge type.
+    Directedness { session_directed: bool },
+    /// Several nodes have the same index.
+    DuplicateNode(usize),
+    /// Several edges have the same index.
+    DuplicateEdge(usize),
+    /// Edge references a node which is not in the session.
+    MissingNode { edge: usize, node: usize },
+    /// Node index does not fit into the index type or leaves too large gap after other indices.
+    NodeOutOfRange(usize),
+    /// Edge index does not fit into the index type or leaves too large gap after other indices.
+    EdgeOutOfRange(usize),
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Directedness { session_directed } => {
+                let (found, expected) = if *session_directed {
+                    ("directed", "undirected")
+                } else {
+                    ("undirected", "directed")
+                };
+                write!(f, "session contains {found} graph, expected {expected}")
+            }
+            Self::DuplicateNode(idx) => write!(f, "duplicate node {idx}"),
+            Self::DuplicateEdge(idx) => write!(f, "duplicate edge {idx}"),
+            Self::MissingNode { edge, node } => {
+                write!(f, "edge {edge} references missing node {node}")
+            }
+            Self::NodeOutOfRange(idx) => write!(f, "node index {idx} is out of range"),
+            Self::EdgeOutOfRange(idx) => write!(f, "edge index {idx} is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+/// Node as it is stored in a [`Session`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionNode<N> {
+    /// Index of the node in the graph, preserved on restore.
+    pub id: usize,
+    pub payload: N,
+    pub label: String,
+    /// Location assigned by the layout.
+    pub location: Pos2,
+    /// Location set by the user. It overrides the layout location and pins the node in place.
+    #[serde(default)]
+    pub location_user: Option<Pos2>,
+    #[serde(default)]
+    pub color: Option<Color32>,
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// Edge as it is stored in a [`Session`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEdge<E> {
+    /// Index of the edge in the graph, preserved on restore.
+    pub id: usize,
+    pub source: usize,
+    pub target: usize,
+    pub payload: E,
+    pub label: String,
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// Zoom and pan of the [`crate::GraphView`]. Pan is relative to the top left corner of the widget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionCamera {
+    pub zoom: f32,
+    pub pan: Vec2,
+}
+
+/// Snapshot of a graph workspace: nodes and edges with their positions, pins and selection,
+/// camera and layout state.
+///
+/// Session is serialized with a `version` tag and older versions are migrated to the latest one
+/// on deserialization, so files saved by previous versions of the crate can still be opened.
+/// The format relies on the tag to pick the schema, so it needs a self-describing serde format,
+/// e.g. JSON or RON.
+///
+/// Use [`Session::from_graph`] and [`Session::into_graph`] for the graph only or
+/// [`crate::GraphView::save_session`] and [`crate::GraphView::load_session`] to also capture
+/// the camera and the layout state kept in egui memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session<N, E, S = ()> {
+    pub directed: bool,
+    pub nodes: Vec<SessionNode<N>>,
+    pub edges: Vec<SessionEdge<E>>,
+    pub camera: Option<SessionCamera>,
+    pub layout: Option<S>,
+}
+
+impl<N, E, S> Session<N, E, S>
+where
+    N: Clone,
+    E: Clone,
+{
+    /// Captures the graph without camera and layout state.
+    pub fn from_graph<Ty, Ix, Dn, De>(g: &Graph<N, E, Ty, Ix, Dn, De>) -> Self
+    where
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        let nodes = g
+            .nodes_iter()
+            .map(|(idx, n)| {
+                let props = n.props();
+                SessionNode {
+                    id: idx.index(),
+                    payload: props.payload.clone(),
+                    label: props.label.clone(),
+                    location: props.location,
+                    location_user: props.location_user,
+                    color: props.color,
+                    selected: props.selected,
+                }
+            })
+            .collect();
+
+        let edges = g
+            .edges_iter()
+            .filter_map(|(idx, e)| {
+                let (source, target) = g.edge_endpoints(idx)?;
+                let props = e.props();
+                Some(SessionEdge {
+                    id: idx.index(),
+                    source: source.index(),
+                    target: target.index(),
+                    payload: props.payload.clone(),
+                    label: props.label.clone(),
+                    selected: props.selected,
+                })
+            })
+            .collect();
+
+        Self {
+            directed: g.is_directed(),
+            nodes,
+            edges,
+            camera: Option::default(),
+            layout: Option::default(),
+        }
+    }
+
+    /// Restores the graph. Node and edge indices are the same as in the saved graph.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SessionError`] if the session is inconsistent or was saved from a graph with
+    /// a different edge type.
+    pub fn into_graph<Ty, Ix, Dn, De>(self) -> Result<Graph<N, E, Ty, Ix, Dn, De>, SessionError>
+    where
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        if self.directed != Ty::is_directed() {
+            return Err(SessionError::Directedness {
+                session_directed: self.directed,
+            });
+        }
+
+        let mut g = StableGraph::with_capacity(self.nodes.len(), self.edges.len());
+
+        // holes in the indices are filled with placeholders which are removed afterwards
+        let max_idx = <Ix as IndexType>::max().index();
+        let in_range = |id: usize, count: usize| id < max_idx && id < count + MAX_INDEX_GAP;
+
+        let mut node_placeholders = HashSet::new();
+        let mut nodes = self.nodes;
+        nodes.sort_by_key(|n| n.id);
+        let nodes_count = nodes.len();
+        for n in nodes {
+            if !in_range(n.id, nodes_count) {
+                return Err(SessionError::NodeOutOfRange(n.id));
+            }
+            if n.id < g.node_bound() {
+                return Err(SessionError::DuplicateNode(n.id));
+            }
+            while g.node_bound() < n.id {
+                node_placeholders.insert(g.add_node(Node::new(n.payload.clone())));
+            }
+
+            let idx = g.add_node(Node::new_with_props(NodeProps {
+                payload: n.payload,
+                label: n.label,
+                selected: n.selected,
+                dragged: false,
+                hovered: false,
+                focused: false,
+                highlight: Highlight::default(),
+                color: n.color,
+                location: n.location,
+                location_user: n.location_user,
+            }));
+            g[idx].set_id(idx);
+        }
+
+        let mut edge_placeholders = vec![];
+        let mut edges = self.edges;
+        edges.sort_by_key(|e| e.id);
+        let edges_count = edges.len();
+        for e in edges {
+            if !in_range(e.id, edges_count) {
+                return Err(SessionError::EdgeOutOfRange(e.id));
+            }
+            if e.id < g.edge_bound() {
+                return Err(SessionError::DuplicateEdge(e.id));
+            }
+
+            for node in [e.source, e.target] {
+                // indices which do not fit into the index type would be truncated
+                if node >= g.node_bound()
+                    || g.node_weight(NodeIndex::new(node)).is_none()
+                    || node_placeholders.contains(&NodeIndex::new(node))
+                {
+                    return Err(SessionError::MissingNode { edge: e.id, node });
+                }
+            }
+            let (source, target) = (NodeIndex::new(e.source), NodeIndex::new(e.target));
+
+            while g.edge_bound() < e.id {
+                edge_placeholders.push(g.add_edge(source, target, Edge::new(e.payload.clone())));
+            }
+
+            let mut edge = Edge::new(e.payload);
+            edge.set_label(e.label);
+            edge.set_selected(e.selected);
+            let idx = g.add_edge(source, target, edge);
+            g[idx].set_id(idx);
+        }
+
+        for idx in edge_placeholders {
+            g.remove_edge(idx);
+        }
+        for idx in node_placeholders {
+            g.remove_node(idx);
+        }
+
+        // order of the edge among the edges connecting the same nodes
+        let edge_indices = g.edge_indices().collect::<Vec<_>>();
+        for idx in edge_indices {
+            let Some((start, end)) = g.edge_endpoints(idx) else {
+                continue;
+            };
+            let order = g
+                .edges_connecting(start, end)
+                .filter(|e| e.id() < idx)
+                .count();
+            g[idx].set_order(order);
+        }
+
+        let mut res = Graph::new(g);
+        let selected_nodes = res
+            .nodes_iter()
+            .filter(|(_, n)| n.selected())
+            .map(|(idx, _)| idx)
+            .collect::<Vec<NodeIndex<Ix>>>();
+        let selected_edges = res
+            .edges_iter()
+            .filter(|(_, e)| e.selected())
+            .map(|(idx, _)| idx)
+            .collect::<Vec<EdgeIndex<Ix>>>();
+        res.set_selected_nodes(selected_nodes);
+        res.set_selected_edges(selected_edges);
+
+        Ok(res)
+    }
+
+    /// Adds camera to the session.
+    pub fn with_camera(mut self, camera: SessionCamera) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    /// Adds layout state to the session.
+    pub fn with_layout(mut self, layout: S) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+}
+
+impl SessionCamera {
+    pub(crate) fn from_metadata(meta: &Metadata) -> Self {
+        Self {
+            zoom: meta.zoom,
+            pan: meta.pan - meta.top_left.to_vec2(),
+        }
+    }
+
+    pub(crate) fn to_metadata(&self) -> Metadata {
+        let mut meta = Metadata::default();
+        meta.first_frame = false;
+        meta.zoom = self.zoom;
+        meta.pan = self.pan;
+        meta.top_left = Pos2::ZERO;
+        meta
+    }
+}
+
+/// Serialized form of the session. Every schema change adds a new variant, old variants stay
+/// untouched and are migrated to the latest one in [`Versioned::into_latest`].
+#[derive(Deserialize)]
+#[serde(tag = "version")]
+enum Versioned<N, E, S> {
+    #[serde(rename = "1")]
+    V1 {
+        directed: bool,
+        nodes: Vec<SessionNode<N>>,
+        edges: Vec<SessionEdge<E>>,
+        #[serde(default = "Option::default")]
+        camera: Option<SessionCamera>,
+        #[serde(default = "Option::default")]
+        layout: Option<S>,
+    },
+}
+
+impl<N, E, S> Versioned<N, E, S> {
+    fn into_latest(self) -> Session<N, E, S> {
+        match self {
+            Self::V1 {
+                directed,
+                nodes,
+                edges,
+                camera,
+                layout,
+            } => Session {
+                directed,
+                nodes,
+                edges,
+                camera,
+                layout,
+            },
+        }
+    }
+}
+
+/// Latest schema of [`Versioned`] borrowing the session for serialization.
+#[derive(Serialize)]
+#[serde(tag = "version")]
+enum VersionedRef<'a, N, E, S> {
+    #[serde(rename = "1")]
+    V1 {
+        directed: bool,
+        nodes: &'a [SessionNode<N>],
+        edges: &'a [SessionEdge<E>],
+        camera: &'a Option<SessionCamera>,
+        layout: &'a Option<S>,
+    },
+}
+
+impl<N, E, S> Serialize for Session<N, E, S>
+where
+    N: Serialize,
+    E: Serialize,
+    S: Serialize,
+{
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        VersionedRef::V1 {
+            directed: self.directed,
+            nodes: &self.nodes,
+            edges: &self.edges,
+            camera: &self.camera,
+            layout: &self.layout,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, N, E, S> Deserialize<'de> for Session<N, E, S>
+where
+    N: Deserialize<'de>,
+    E: Deserialize<'de>,
+    S: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Versioned::deserialize(deserializer).map(Versioned::into_latest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{Directed, Undirected};
+
+    use super::*;
+    use crate::{DefaultEdgeShape, DefaultNodeShape};
+
+    fn graph_with_holes() -> Graph<String, u32> {
+        let mut graph: Graph<String, u32> = Graph::new(StableGraph::default());
+        let [a, b, c, d] = ["a", "b", "c", "d"].map(|n| graph.add_node(n.to_string()));
+        graph.add_edge(a, b, 1);
+        let removed = graph.add_edge(b, c, 2);
+        graph.add_edge(a, d, 3);
+        graph.add_edge(a, d, 4);
+        graph.remove_edge(removed);
+        graph.remove_node(c);
+        graph.node_mut(d).unwrap().set_selected(true);
+
+        graph
+    }
+
+    #[test]
+    fn test_session_roundtrip_preserves_indices() {
+        let g = graph_with_holes();
+        let session: Session<String, u32> = Session::from_graph(&g);
+
+        let json = serde_json::to_string(&session).unwrap();
+        let restored: Session<String, u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, session);
+
+        let restored: Graph<String, u32> = restored.into_graph().unwrap();
+        assert_eq!(restored.node_count(), 3);
+        assert_eq!(restored.edge_count(), 3);
+        for (idx, n) in g.nodes_iter() {
+            let r = restored.node(idx).unwrap();
+            assert_eq!(r.payload(), n.payload());
+            assert_eq!(r.location(), n.location());
+        }
+        for (idx, e) in g.edges_iter() {
+            let r = restored.edge(idx).unwrap();
+            assert_eq!(r.payload(), e.payload());
+            assert_eq!(r.order(), e.order());
+            assert_eq!(restored.edge_endpoints(idx), g.edge_endpoints(idx));
+        }
+        assert_eq!(restored.selected_nodes(), &[NodeIndex::new(3)]);
+    }
+
+    #[test]
+    fn test_session_is_versioned() {
+        let session: Session<(), ()> =
+            Session::from_graph(&Graph::<(), ()>::new(StableGraph::default()));
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["version"], "1");
+
+        let unknown = r#"{"version":"0","directed":true,"nodes":[],"edges":[]}"#;
+        assert!(serde_json::from_str::<Session<(), ()>>(unknown).is_err());
+    }
+
+    #[test]
+    fn test_session_errors() {
+        let session: Session<String, u32> = Session::from_graph(&graph_with_holes());
+
+        let res = session
+            .clone()
+            .into_graph::<Undirected, u32, DefaultNodeShape, DefaultEdgeShape>();
+        assert_eq!(
+            res.err(),
+            Some(SessionError::Directedness {
+                session_directed: true
+            })
+        );
+
+        let mut broken = session;
+        broken.edges[0].target = 2;
+        let res = broken.into_graph::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>();
+        assert_eq!(
+            res.err(),
+            Some(SessionError::MissingNode { edge: 0, node: 2 })
+        );
+    }
+
+    #[test]
+    fn test_session_out_of_range() {
+        let session: Session<String, u32> = Session::from_graph(&graph_with_holes());
+
+        let mut broken = session.clone();
+        broken.nodes[0].id = 4_294_967_000;
+        let res = broken.into_graph::<Directed, u32, DefaultNodeShape, DefaultEdgeShape>();
+        assert_eq!(res.err(), Some(SessionError::NodeOutOfRange(4_294_967_000)));
+
+        let mut broken = session.clone();
+        broken.edges[0].id = 300;
+        let res = broken.into_graph::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>();
+        assert_eq!(res.err(), Some(SessionError::EdgeOutOfRange(300)));
+
+        let mut broken = session;
+        broken.edges[0].source = 256;
+        let res = broken.into_graph::<Directed, u8, DefaultNodeShape, DefaultEdgeShape>();
+        assert_eq!(
+            res.err(),
+            Some(SessionError::MissingNode { edge: 0, node: 256 })
+        );
+    }
+}
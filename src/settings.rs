@@ -1,5 +1,7 @@
+use egui::{Modifiers, Vec2};
+
 /// Represents graph interaction settings.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct SettingsInteraction {
     pub(crate) dragging_enabled: bool,
     pub(crate) node_clicking_enabled: bool,
@@ -8,6 +10,50 @@ pub struct SettingsInteraction {
     pub(crate) edge_clicking_enabled: bool,
     pub(crate) edge_selection_enabled: bool,
     pub(crate) edge_selection_multi_enabled: bool,
+    pub(crate) box_selection_enabled: bool,
+    pub(crate) box_selection_edges_enabled: bool,
+    pub(crate) lasso_selection_enabled: bool,
+    pub(crate) lasso_selection_modifier: Modifiers,
+    pub(crate) box_selection_modifier: Modifiers,
+    pub(crate) box_selection_add_modifier: Modifiers,
+    pub(crate) box_selection_remove_modifier: Modifiers,
+    pub(crate) edge_creation_enabled: bool,
+    pub(crate) edge_creation_modifier: Modifiers,
+    pub(crate) edge_creation_self_loops_enabled: bool,
+    pub(crate) edge_creation_duplicates_enabled: bool,
+    pub(crate) editing_enabled: bool,
+    pub(crate) undo_redo_keys_enabled: bool,
+    pub(crate) label_editing_enabled: bool,
+    pub(crate) keyboard_navigation_enabled: bool,
+}
+
+impl Default for SettingsInteraction {
+    fn default() -> Self {
+        Self {
+            dragging_enabled: bool::default(),
+            node_clicking_enabled: bool::default(),
+            node_selection_enabled: bool::default(),
+            node_selection_multi_enabled: bool::default(),
+            edge_clicking_enabled: bool::default(),
+            edge_selection_enabled: bool::default(),
+            edge_selection_multi_enabled: bool::default(),
+            box_selection_enabled: bool::default(),
+            box_selection_edges_enabled: bool::default(),
+            lasso_selection_enabled: bool::default(),
+            lasso_selection_modifier: Modifiers::ALT,
+            box_selection_modifier: Modifiers::SHIFT,
+            box_selection_add_modifier: Modifiers::COMMAND,
+            box_selection_remove_modifier: Modifiers::ALT,
+            edge_creation_enabled: bool::default(),
+            edge_creation_modifier: Modifiers::SHIFT,
+            edge_creation_self_loops_enabled: bool::default(),
+            edge_creation_duplicates_enabled: bool::default(),
+            editing_enabled: bool::default(),
+            undo_redo_keys_enabled: bool::default(),
+            label_editing_enabled: bool::default(),
+            keyboard_navigation_enabled: bool::default(),
+        }
+    }
 }
 
 impl SettingsInteraction {
@@ -79,6 +125,160 @@ impl SettingsInteraction {
         self.edge_selection_multi_enabled = enabled;
         self
     }
+
+    /// Selects nodes with a rectangle drawn by dragging on the empty canvas while holding
+    /// the box selection modifier.
+    ///
+    /// Nodes which shapes intersect the rectangle replace the current selection. Holding
+    /// the add or the remove modifier in addition adds them to the selection or removes them from it.
+    ///
+    /// Default: `false`
+    pub fn with_box_selection_enabled(mut self, enabled: bool) -> Self {
+        self.box_selection_enabled = enabled;
+        self
+    }
+
+    /// Box selection also selects edges which are fully inside of the rectangle.
+    ///
+    /// Default: `false`
+    pub fn with_box_selection_edges_enabled(mut self, enabled: bool) -> Self {
+        self.box_selection_edges_enabled = enabled;
+        self
+    }
+
+    /// Selects nodes with a free-form lasso drawn by dragging on the empty canvas while holding
+    /// the lasso selection modifier. Nodes which centers are inside of the lasso or which shapes
+    /// are crossed by it are selected.
+    ///
+    /// Uses the same add and remove modifiers and edges setting as the box selection and works
+    /// together with it or on its own. The box selection is started when both of the modifiers
+    /// are held.
+    ///
+    /// Default: `false`
+    pub fn with_lasso_selection_enabled(mut self, enabled: bool) -> Self {
+        self.lasso_selection_enabled = enabled;
+        self
+    }
+
+    /// Modifier which should be held to start the lasso selection. The add or the remove modifier
+    /// which is a part of it is not taken into account, e.g. the lasso can't remove nodes with
+    /// the default modifiers.
+    ///
+    /// Default: [`Modifiers::ALT`]
+    pub fn with_lasso_selection_modifier(mut self, modifier: Modifiers) -> Self {
+        self.lasso_selection_modifier = modifier;
+        self
+    }
+
+    /// Modifier which should be held to start the box selection.
+    ///
+    /// Default: [`Modifiers::SHIFT`]
+    pub fn with_box_selection_modifier(mut self, modifier: Modifiers) -> Self {
+        self.box_selection_modifier = modifier;
+        self
+    }
+
+    /// Modifier which makes the box selection add to the current selection when held together
+    /// with the box selection modifier.
+    ///
+    /// Default: [`Modifiers::COMMAND`]
+    pub fn with_box_selection_add_modifier(mut self, modifier: Modifiers) -> Self {
+        self.box_selection_add_modifier = modifier;
+        self
+    }
+
+    /// Modifier which makes the box selection remove from the current selection when held
+    /// together with the box selection modifier.
+    ///
+    /// Default: [`Modifiers::ALT`]
+    pub fn with_box_selection_remove_modifier(mut self, modifier: Modifiers) -> Self {
+        self.box_selection_remove_modifier = modifier;
+        self
+    }
+
+    /// Draws edges by dragging from one node to another while holding the edge creation modifier.
+    ///
+    /// Releasing the pointer over the target node sends the `EdgeCreateRequest` event and creates
+    /// the edge with the payload returned by the edge factory set with
+    /// [`GraphView::with_edge_factory`](crate::GraphView::with_edge_factory).
+    ///
+    /// Default: `false`
+    pub fn with_edge_creation_enabled(mut self, enabled: bool) -> Self {
+        self.edge_creation_enabled = enabled;
+        self
+    }
+
+    /// Modifier which should be held to start drawing an edge from a node, other modifiers can be
+    /// held together with it. Use [`Modifiers::NONE`] to draw edges with a plain drag, node
+    /// dragging is not possible then.
+    ///
+    /// Default: [`Modifiers::SHIFT`]
+    pub fn with_edge_creation_modifier(mut self, modifier: Modifiers) -> Self {
+        self.edge_creation_modifier = modifier;
+        self
+    }
+
+    /// Allows drawing edges from a node to itself.
+    ///
+    /// Default: `false`
+    pub fn with_edge_creation_self_loops_enabled(mut self, enabled: bool) -> Self {
+        self.edge_creation_self_loops_enabled = enabled;
+        self
+    }
+
+    /// Allows drawing edges between nodes which are already connected.
+    ///
+    /// Default: `false`
+    pub fn with_edge_creation_duplicates_enabled(mut self, enabled: bool) -> Self {
+        self.edge_creation_duplicates_enabled = enabled;
+        self
+    }
+
+    /// Editing mode for the graph structure:
+    /// * double click on the empty space creates a node with the payload returned by the node
+    ///   factory set with [`GraphView::with_node_factory`](crate::GraphView::with_node_factory);
+    /// * `Delete` or `Backspace` removes selected nodes and edges while the graph has the keyboard
+    ///   focus or the pointer is over it;
+    /// * dragging a handle at the end of a selected edge reconnects the edge to another node.
+    ///
+    /// Reconnected edges respect self loops and duplicates settings of the edge creation.
+    ///
+    /// Default: `false`
+    pub fn with_editing_enabled(mut self, enabled: bool) -> Self {
+        self.editing_enabled = enabled;
+        self
+    }
+
+    /// Undo with `Ctrl+Z` and redo with `Ctrl+Shift+Z` or `Ctrl+Y` (`Cmd` on Mac) while the pointer
+    /// is over the graph. Changes are recorded only if the history is enabled with
+    /// [`Graph::set_history_enabled`](crate::Graph::set_history_enabled).
+    ///
+    /// Default: `false`
+    pub fn with_undo_redo_keys_enabled(mut self, enabled: bool) -> Self {
+        self.undo_redo_keys_enabled = enabled;
+        self
+    }
+
+    /// Edits labels in place: double click on a node or an edge, or `F2` with a selected element,
+    /// opens a text field at the label. `Enter` or clicking outside of the field applies
+    /// the label, `Escape` cancels editing.
+    ///
+    /// Default: `false`
+    pub fn with_label_editing_enabled(mut self, enabled: bool) -> Self {
+        self.label_editing_enabled = enabled;
+        self
+    }
+
+    /// Controls the graph from the keyboard while the widget has focus. `Tab` and `Shift+Tab`
+    /// move the focus between nodes, arrows move it to the nearest node in that direction
+    /// and `Enter` selects the focused node. If zoom and pan are enabled `+` and `-` zoom
+    /// and arrows with `Ctrl` (`Cmd` on Mac) pan the graph.
+    ///
+    /// Default: `false`
+    pub fn with_keyboard_navigation_enabled(mut self, enabled: bool) -> Self {
+        self.keyboard_navigation_enabled = enabled;
+        self
+    }
 }
 
 /// Represents graph navigation settings.
@@ -88,6 +288,13 @@ pub struct SettingsNavigation {
     pub(crate) zoom_and_pan_enabled: bool,
     pub(crate) screen_padding: f32,
     pub(crate) zoom_speed: f32,
+    pub(crate) zoom_min: f32,
+    pub(crate) zoom_max: f32,
+    pub(crate) pan_bounds_enabled: bool,
+    pub(crate) scroll_zoom_enabled: bool,
+    pub(crate) scroll_pan_enabled: bool,
+    pub(crate) minimap_enabled: bool,
+    pub(crate) minimap_size: Vec2,
 }
 
 impl Default for SettingsNavigation {
@@ -97,6 +304,13 @@ impl Default for SettingsNavigation {
             zoom_speed: 0.1,
             fit_to_screen_enabled: true,
             zoom_and_pan_enabled: false,
+            zoom_min: f32::MIN_POSITIVE,
+            zoom_max: f32::INFINITY,
+            pan_bounds_enabled: false,
+            scroll_zoom_enabled: false,
+            scroll_pan_enabled: false,
+            minimap_enabled: false,
+            minimap_size: Vec2::new(200., 150.),
         }
     }
 }
@@ -138,12 +352,108 @@ impl SettingsNavigation {
         self.zoom_speed = speed;
         self
     }
+
+    /// Limits the zoom factor. The limits also apply to fitting to the screen
+    /// and to the camera moves.
+    ///
+    /// The zoom always stays positive: a `min` that is not a positive finite number leaves the
+    /// zoom unbounded below. A `max` that is `NaN` leaves it unbounded above and a `max` below
+    /// `min` is raised to `min`.
+    ///
+    /// Default: unbounded
+    pub fn with_zoom_limits(mut self, min: f32, max: f32) -> Self {
+        self.zoom_min = if min.is_finite() && min > 0. {
+            min
+        } else {
+            f32::MIN_POSITIVE
+        };
+        self.zoom_max = if max.is_nan() {
+            f32::INFINITY
+        } else {
+            max.max(self.zoom_min)
+        };
+        self
+    }
+
+    /// Constrains panning so a part of the graph always stays visible.
+    ///
+    /// Default: `false`
+    pub fn with_pan_bounds_enabled(mut self, enabled: bool) -> Self {
+        self.pan_bounds_enabled = enabled;
+        self
+    }
+
+    /// Zoom with mouse wheel without holding ctrl.
+    ///
+    /// Default: `false`
+    pub fn with_scroll_zoom_enabled(mut self, enabled: bool) -> Self {
+        self.scroll_zoom_enabled = enabled;
+        self
+    }
+
+    /// Pan with scrolling, e.g. two-finger swipe on a trackpad. Scroll zoom takes precedence
+    /// if both are enabled.
+    ///
+    /// Default: `false`
+    pub fn with_scroll_pan_enabled(mut self, enabled: bool) -> Self {
+        self.scroll_pan_enabled = enabled;
+        self
+    }
+
+    /// Shows the whole graph in the bottom right corner of the widget together with
+    /// the visible area. Click or drag on the minimap to move the view.
+    ///
+    /// Default: `false`
+    pub fn with_minimap_enabled(mut self, enabled: bool) -> Self {
+        self.minimap_enabled = enabled;
+        self
+    }
+
+    /// Size of the minimap in screen pixels.
+    ///
+    /// Default: `200x150`
+    pub fn with_minimap_size(mut self, size: Vec2) -> Self {
+        self.minimap_size = size;
+        self
+    }
+}
+
+/// Direction of the edges followed when looking for the neighborhood of a node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HighlightDirection {
+    /// Follow edges pointing to the node.
+    Incoming,
+    /// Follow edges pointing from the node.
+    Outgoing,
+    /// Follow all edges. Undirected graphs always follow all edges.
+    #[default]
+    Both,
 }
 
 /// `SettingsStyle` stores settings for the style of the graph.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct SettingsStyle {
     pub(crate) labels_always: bool,
+    pub(crate) highlight_neighbors_enabled: bool,
+    pub(crate) highlight_depth: usize,
+    pub(crate) highlight_direction: HighlightDirection,
+    pub(crate) lod_labels_zoom: f32,
+    pub(crate) lod_nodes_zoom: f32,
+    pub(crate) lod_edges_zoom: f32,
+}
+
+impl Default for SettingsStyle {
+    fn default() -> Self {
+        Self {
+            labels_always: bool::default(),
+            highlight_neighbors_enabled: bool::default(),
+            highlight_depth: 1,
+            highlight_direction: HighlightDirection::default(),
+            lod_labels_zoom: 0.,
+            lod_nodes_zoom: 0.,
+            lod_edges_zoom: 0.,
+        }
+    }
 }
 
 impl SettingsStyle {
@@ -163,4 +473,53 @@ impl SettingsStyle {
         self.labels_always = always;
         self
     }
+
+    /// Emphasizes the neighborhood of the hovered node, or of the selected nodes when nothing
+    /// is hovered, and dims the rest of the graph.
+    ///
+    /// Default is false.
+    pub fn with_highlight_neighbors_enabled(mut self, enabled: bool) -> Self {
+        self.highlight_neighbors_enabled = enabled;
+        self
+    }
+
+    /// Number of hops from the highlighted nodes which belong to the neighborhood.
+    ///
+    /// Default is 1.
+    pub fn with_highlight_depth(mut self, depth: usize) -> Self {
+        self.highlight_depth = depth;
+        self
+    }
+
+    /// Direction of the edges followed to find the neighborhood.
+    ///
+    /// Default is [`HighlightDirection::Both`].
+    pub fn with_highlight_direction(mut self, direction: HighlightDirection) -> Self {
+        self.highlight_direction = direction;
+        self
+    }
+
+    /// Below this zoom labels are not shown always, only for the interacted elements.
+    ///
+    /// Default is 0, labels are never hidden.
+    pub fn with_lod_labels_zoom(mut self, zoom: f32) -> Self {
+        self.lod_labels_zoom = zoom;
+        self
+    }
+
+    /// Below this zoom nodes are drawn as points.
+    ///
+    /// Default is 0, nodes are never simplified.
+    pub fn with_lod_nodes_zoom(mut self, zoom: f32) -> Self {
+        self.lod_nodes_zoom = zoom;
+        self
+    }
+
+    /// Below this zoom edges are drawn as straight lines without tips and labels.
+    ///
+    /// Default is 0, edges are never simplified.
+    pub fn with_lod_edges_zoom(mut self, zoom: f32) -> Self {
+        self.lod_edges_zoom = zoom;
+        self
+    }
 }
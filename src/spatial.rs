@@ -0,0 +1,240 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use egui::{Rect, Vec2};
+use petgraph::stable_graph::{EdgeIndex, IndexType, NodeIndex};
+use petgraph::EdgeType;
+
+use crate::{node_size, DisplayNode, Node};
+
+/// Size of the grid cell in canvas coordinates.
+const CELL_SIZE: f32 = 100.;
+
+/// Items covering more cells are not put to the cells and are checked on every query.
+const MAX_ITEM_CELLS: i64 = 64;
+
+/// Loop size in node radiuses which is reserved around the looped edges.
+const EDGE_LOOP_SIZE: f32 = 4.;
+
+/// Distance between parallel curved edges which is reserved around the edges.
+const EDGE_SPREAD: f32 = 20.;
+
+type Cell = (i32, i32);
+
+/// Spatial index of the graph elements bounds.
+///
+/// Elements changed since the last update are tracked as dirty. Their indexed bounds are not
+/// used until they are reindexed.
+#[derive(Debug, Clone)]
+pub(crate) struct SpatialIndex<Ix: IndexType> {
+    pub nodes: Grid<NodeIndex<Ix>>,
+    pub edges: Grid<EdgeIndex<Ix>>,
+    /// Nodes added, removed or changed since the last update
+    pub dirty_nodes: HashSet<NodeIndex<Ix>>,
+    /// Edges added, removed or changed since the last update
+    pub dirty_edges: HashSet<EdgeIndex<Ix>>,
+    /// The whole index is outdated and has to be rebuilt
+    pub stale: bool,
+}
+
+impl<Ix: IndexType> Default for SpatialIndex<Ix> {
+    fn default() -> Self {
+        Self {
+            nodes: Grid::default(),
+            edges: Grid::default(),
+            dirty_nodes: HashSet::default(),
+            dirty_edges: HashSet::default(),
+            stale: true,
+        }
+    }
+}
+
+/// Bounds of the node shape in canvas coordinates.
+pub(crate) fn node_rect<N, E, Ty, Ix, D>(n: &Node<N, E, Ty, Ix, D>) -> Rect
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    D: DisplayNode<N, E, Ty, Ix>,
+{
+    let size = Vec2::new(node_size(n, Vec2::X), node_size(n, Vec2::Y));
+    Rect::from_center_size(n.location(), size * 2.)
+}
+
+/// Approximate bounds of the edge in canvas coordinates given the bounds of its nodes.
+/// Loops and curves of the parallel edges grow with the edge order so the bounds do too.
+pub(crate) fn edge_rect(start: Rect, end: Rect, order: usize) -> Rect {
+    let radius = start.size().max_elem().max(end.size().max_elem()) / 2.;
+    let order = order as f32;
+    start
+        .union(end)
+        .expand(radius * (EDGE_LOOP_SIZE + order) + EDGE_SPREAD * (order + 1.))
+}
+
+/// Uniform grid of items bounding rects in canvas coordinates. It is used to find nodes
+/// and edges by position without checking every element of the graph.
+///
+/// Items are moved between cells only when their rects change, so updating the grid
+/// for a graph where few nodes move is cheap.
+#[derive(Debug, Clone)]
+pub(crate) struct Grid<K> {
+    cells: HashMap<Cell, Vec<K>>,
+    /// Items which cover too many cells, e.g. long edges
+    oversized: HashSet<K>,
+    items: HashMap<K, Rect>,
+}
+
+impl<K> Default for Grid<K> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::default(),
+            oversized: HashSet::default(),
+            items: HashMap::default(),
+        }
+    }
+}
+
+impl<K> Grid<K>
+where
+    K: Copy + Eq + Hash + Ord,
+{
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn rect(&self, key: K) -> Option<Rect> {
+        self.items.get(&key).copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.oversized.clear();
+        self.items.clear();
+    }
+
+    /// Inserts the item or moves it if its rect has changed. Returns true if the grid was changed.
+    pub fn insert(&mut self, key: K, rect: Rect) -> bool {
+        if self.items.get(&key) == Some(&rect) {
+            return false;
+        }
+
+        self.remove(key);
+        self.items.insert(key, rect);
+        match cell_range(rect) {
+            Some((min, max)) => {
+                for x in min.0..=max.0 {
+                    for y in min.1..=max.1 {
+                        self.cells.entry((x, y)).or_default().push(key);
+                    }
+                }
+            }
+            None => {
+                self.oversized.insert(key);
+            }
+        }
+        true
+    }
+
+    pub fn remove(&mut self, key: K) {
+        let Some(rect) = self.items.remove(&key) else {
+            return;
+        };
+
+        let Some((min, max)) = cell_range(rect) else {
+            self.oversized.remove(&key);
+            return;
+        };
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(cell) = self.cells.get_mut(&(x, y)) {
+                    cell.retain(|k| *k != key);
+                    if cell.is_empty() {
+                        self.cells.remove(&(x, y));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns items which rects intersect the given rect, sorted by key.
+    pub fn query(&self, rect: Rect) -> Vec<K> {
+        let mut found = HashSet::new();
+        let candidates = self.oversized.iter();
+        match cell_range(rect) {
+            Some((min, max)) => {
+                let in_cells = (min.0..=max.0)
+                    .flat_map(|x| (min.1..=max.1).map(move |y| (x, y)))
+                    .filter_map(|cell| self.cells.get(&cell))
+                    .flatten();
+                for key in candidates.chain(in_cells) {
+                    if self.items[key].intersects(rect) {
+                        found.insert(*key);
+                    }
+                }
+            }
+            // query is too big for the cells lookup
+            None => {
+                found.extend(
+                    self.items
+                        .iter()
+                        .filter(|(_, r)| r.intersects(rect))
+                        .map(|(k, _)| *k),
+                );
+            }
+        }
+
+        let mut found = found.into_iter().collect::<Vec<_>>();
+        found.sort_unstable();
+        found
+    }
+}
+
+/// Returns the range of cells covered by the rect or None if there are too many of them.
+fn cell_range(rect: Rect) -> Option<(Cell, Cell)> {
+    let to_cell = |v: f32| (v / CELL_SIZE).floor();
+    let (min_x, min_y) = (to_cell(rect.min.x), to_cell(rect.min.y));
+    let (max_x, max_y) = (to_cell(rect.max.x), to_cell(rect.max.y));
+    if !(min_x.is_finite() && min_y.is_finite() && max_x.is_finite() && max_y.is_finite()) {
+        return None;
+    }
+
+    let count = (f64::from(max_x - min_x) + 1.) * (f64::from(max_y - min_y) + 1.);
+    if count > MAX_ITEM_CELLS as f64 {
+        return None;
+    }
+
+    Some(((min_x as i32, min_y as i32), (max_x as i32, max_y as i32)))
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Pos2;
+
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::default();
+        let point = |x, y| Rect::from_center_size(Pos2::new(x, y), Vec2::splat(10.));
+        grid.insert(1, point(0., 0.));
+        grid.insert(2, point(250., 250.));
+        grid.insert(
+            3,
+            Rect::from_min_max(Pos2::new(-1e4, 0.), Pos2::new(1e4, 1.)),
+        );
+        assert_eq!(grid.len(), 3);
+
+        assert_eq!(grid.query(point(2., 2.)), vec![1, 3]);
+        assert_eq!(grid.query(point(250., 250.)), vec![2]);
+
+        assert!(grid.insert(1, point(250., 245.)));
+        assert!(!grid.insert(1, point(250., 245.)));
+        assert_eq!(grid.query(point(250., 250.)), vec![1, 2]);
+
+        grid.remove(2);
+        grid.remove(3);
+        assert_eq!(grid.query(Rect::EVERYTHING), vec![1]);
+        assert_eq!(grid.len(), 1);
+    }
+}